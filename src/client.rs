@@ -1,25 +1,44 @@
 //! Client to manage nodes and players.
 
 use crate::{
-    model::{IncomingEvent, OutgoingEvent},
-    node::{Node, NodeConfig, NodeError, Resume},
+    model::{Destroy, IncomingEvent, OutgoingEvent, VoiceUpdateAccumulator},
+    node::{ConnectionState, Node, NodeAddress, NodeConfig, NodeError, Resume},
     player::{Player, PlayerManager},
 };
-use dashmap::{mapref::one::Ref, DashMap};
+use dashmap::{
+    mapref::one::{Ref, RefMut},
+    DashMap,
+};
 use futures_channel::mpsc::{TrySendError, UnboundedReceiver};
 use std::{
     error::Error,
     fmt::{Display, Formatter, Result as FmtResult},
-    net::SocketAddr,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use twilight_model::{
+    gateway::event::Event,
+    id::{GuildId, UserId},
 };
-use twilight_model::id::{GuildId, UserId};
 
 /// An error that can occur while interacting with the client.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ClientError {
     /// A node isn't configured, so the operation isn't possible to fulfill.
     NodesUnconfigured,
+    /// A player doesn't exist for the guild.
+    PlayerNotFound {
+        /// The ID of the guild.
+        guild_id: GuildId,
+    },
+    /// Sending an event to the node failed because the node's connection was
+    /// shutdown.
+    SendingEvent {
+        /// The source of the error.
+        source: TrySendError<OutgoingEvent>,
+    },
     /// Sending a voice update event to the node failed because the node's
     /// connection was shutdown.
     SendingVoiceUpdate {
@@ -32,6 +51,10 @@ impl Display for ClientError {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
             Self::NodesUnconfigured => f.write_str("no node has been configured"),
+            Self::PlayerNotFound { guild_id } => {
+                write!(f, "no player exists for guild {}", guild_id)
+            }
+            Self::SendingEvent { .. } => f.write_str("couldn't send event to node"),
             Self::SendingVoiceUpdate { .. } => f.write_str("couldn't send voice update to node"),
         }
     }
@@ -41,17 +64,91 @@ impl Error for ClientError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::NodesUnconfigured => None,
+            Self::PlayerNotFound { .. } => None,
+            Self::SendingEvent { source } => Some(source),
             Self::SendingVoiceUpdate { source } => Some(source),
         }
     }
 }
 
+/// The strategy used to select a node for new players.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NodeSelection {
+    /// Select the node with the lowest penalty score.
+    ///
+    /// Refer to [`Node::penalty`] for how this is calculated.
+    ///
+    /// [`Node::penalty`]: ../node/struct.Node.html#method.penalty
+    #[default]
+    LowestPenalty,
+    /// Select each node in turn, in the order of their addresses.
+    RoundRobin,
+    /// Select the node with the fewest players.
+    LeastPlayers,
+}
+
+/// A builder for a [`Lavalink`] client.
+///
+/// [`Lavalink`]: struct.Lavalink.html
+#[derive(Clone, Debug)]
+pub struct LavalinkBuilder {
+    node_selection: NodeSelection,
+    shard_count: u64,
+    user_id: UserId,
+}
+
+impl LavalinkBuilder {
+    /// Create a new builder for a client with the bot's user ID.
+    ///
+    /// The shard count defaults to 1, and the node selection defaults to
+    /// [`NodeSelection::LowestPenalty`].
+    ///
+    /// [`NodeSelection::LowestPenalty`]: enum.NodeSelection.html#variant.LowestPenalty
+    pub fn new(user_id: UserId) -> Self {
+        Self {
+            node_selection: NodeSelection::default(),
+            shard_count: 1,
+            user_id,
+        }
+    }
+
+    /// Set the strategy used to select a node for new players.
+    pub fn node_selection(mut self, node_selection: NodeSelection) -> Self {
+        self.node_selection = node_selection;
+
+        self
+    }
+
+    /// Set the number of shards of the bot.
+    pub fn shard_count(mut self, shard_count: u64) -> Self {
+        self.shard_count = shard_count;
+
+        self
+    }
+
+    /// Consume the builder, creating the client.
+    pub fn build(self) -> Lavalink {
+        Lavalink(Arc::new(LavalinkRef {
+            node_selection: self.node_selection,
+            nodes: DashMap::new(),
+            players: PlayerManager::new(),
+            round_robin: AtomicUsize::new(0),
+            shard_count: self.shard_count,
+            user_id: self.user_id,
+            voice_updates: DashMap::new(),
+        }))
+    }
+}
+
 #[derive(Debug, Default)]
 struct LavalinkRef {
-    guilds: DashMap<GuildId, SocketAddr>,
-    nodes: DashMap<SocketAddr, Node>,
+    node_selection: NodeSelection,
+    nodes: DashMap<NodeAddress, Node>,
     players: PlayerManager,
+    round_robin: AtomicUsize,
+    shard_count: u64,
     user_id: UserId,
+    voice_updates: DashMap<GuildId, VoiceUpdateAccumulator>,
 }
 
 /// The lavalink client that manages nodes, players, and processes events from
@@ -81,12 +178,132 @@ impl Lavalink {
     ///
     /// [`add`]: #method.add
     pub fn new(user_id: UserId) -> Self {
-        Self(Arc::new(LavalinkRef {
-            guilds: DashMap::new(),
-            nodes: DashMap::new(),
-            players: PlayerManager::new(),
-            user_id,
-        }))
+        LavalinkBuilder::new(user_id).build()
+    }
+
+    /// Create a builder to configure a new Lavalink client.
+    pub fn builder(user_id: UserId) -> LavalinkBuilder {
+        LavalinkBuilder::new(user_id)
+    }
+
+    /// Retrieve the strategy used to select a node for new players.
+    pub fn node_selection(&self) -> NodeSelection {
+        self.0.node_selection
+    }
+
+    /// Retrieve the number of shards of the bot.
+    pub fn shard_count(&self) -> u64 {
+        self.0.shard_count
+    }
+
+    /// Process an event from the gateway, sending voice updates to nodes.
+    ///
+    /// This must be called with every Voice State Update and Voice Server
+    /// Update event received, and should be called with every Ready event.
+    /// Other events are ignored.
+    ///
+    /// The events are combined by a [`VoiceUpdateAccumulator`] for each guild.
+    /// Once both have been received, a [`VoiceUpdate`] is sent to the node of
+    /// the guild's player, creating the player if it doesn't exist. A new voice
+    /// update is sent whenever either changes, such as when the bot is moved
+    /// to another channel. Voice server updates without an endpoint are
+    /// ignored until the next voice server update. The stored state for a
+    /// guild is cleared when the bot leaves its voice channel, and the stored
+    /// state for all guilds on a shard is cleared when the shard is ready.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::NodesUnconfigured`] if a player needs to be
+    /// created and no node has been configured via [`add`].
+    ///
+    /// Returns [`ClientError::SendingVoiceUpdate`] if the player's node's
+    /// connection was shutdown.
+    ///
+    /// [`ClientError::NodesUnconfigured`]: enum.ClientError.html#variant.NodesUnconfigured
+    /// [`ClientError::SendingVoiceUpdate`]: enum.ClientError.html#variant.SendingVoiceUpdate
+    /// [`VoiceUpdate`]: ../model/outgoing/struct.VoiceUpdate.html
+    /// [`VoiceUpdateAccumulator`]: ../model/outgoing/struct.VoiceUpdateAccumulator.html
+    /// [`add`]: #method.add
+    pub async fn process(&self, event: &Event) -> Result<(), ClientError> {
+        let (guild_id, update) = match event {
+            Event::Ready(ready) => {
+                let shard_id = ready.shard.map_or(0, |[id, _]| id);
+                self.clear_shard_states(shard_id);
+
+                return Ok(());
+            }
+            Event::VoiceServerUpdate(update) => {
+                let guild_id = match update.guild_id {
+                    Some(guild_id) => guild_id,
+                    None => return Ok(()),
+                };
+
+                // A missing endpoint means the voice server is unavailable, and
+                // a new voice server update will follow once one is allocated.
+                if update.endpoint.is_none() {
+                    tracing::debug!("waiting for a voice server for guild {}", guild_id);
+                }
+
+                (guild_id, self.accumulator(guild_id).voice_server(update))
+            }
+            Event::VoiceStateUpdate(update) => {
+                if update.0.user_id != self.0.user_id {
+                    return Ok(());
+                }
+
+                let guild_id = match update.0.guild_id {
+                    Some(guild_id) => guild_id,
+                    None => return Ok(()),
+                };
+
+                if update.0.channel_id.is_none() {
+                    self.0.voice_updates.remove(&guild_id);
+
+                    return Ok(());
+                }
+
+                (guild_id, self.accumulator(guild_id).voice_state(update))
+            }
+            _ => return Ok(()),
+        };
+
+        let update = match update {
+            Some(update) => update,
+            None => return Ok(()),
+        };
+
+        tracing::debug!("sending voice update for guild {}", guild_id);
+
+        if self.players().get(&guild_id).is_none() {
+            let node = self.best().await?;
+            self.players().get_or_insert(guild_id, node);
+        }
+
+        if let Some(mut player) = self.players().get_mut(&guild_id) {
+            player
+                .send_mut(update)
+                .map_err(|source| ClientError::SendingVoiceUpdate { source })?;
+        }
+
+        Ok(())
+    }
+
+    /// Clear the stored voice states of the guilds on a shard.
+    fn clear_shard_states(&self, shard_id: u64) {
+        let shard_count = self.0.shard_count.max(1);
+
+        self.0
+            .voice_updates
+            .retain(|guild_id, _| (guild_id.0 >> 22) % shard_count != shard_id);
+    }
+
+    /// Retrieve the voice update accumulator of a guild, creating it if it
+    /// doesn't exist.
+    fn accumulator(&self, guild_id: GuildId) -> RefMut<'_, GuildId, VoiceUpdateAccumulator> {
+        self.0
+            .voice_updates
+            .entry(guild_id)
+            .or_insert_with(|| VoiceUpdateAccumulator::new(self.0.user_id, guild_id))
     }
 
     /// Add a new node to be managed by the Lavalink client.
@@ -95,7 +312,7 @@ impl Lavalink {
     /// replaced.
     pub async fn add(
         &self,
-        address: SocketAddr,
+        address: impl Into<NodeAddress>,
         authorization: impl Into<String>,
     ) -> Result<(Node, UnboundedReceiver<IncomingEvent>), NodeError> {
         self.add_with_resume(address, authorization, None).await
@@ -106,44 +323,112 @@ impl Lavalink {
     /// [`add`]: #method.add
     pub async fn add_with_resume(
         &self,
-        address: SocketAddr,
+        address: impl Into<NodeAddress>,
         authorization: impl Into<String>,
         resume: impl Into<Option<Resume>>,
     ) -> Result<(Node, UnboundedReceiver<IncomingEvent>), NodeError> {
-        let config = NodeConfig {
-            address,
-            authorization: authorization.into(),
-            resume: resume.into(),
-            user_id: self.0.user_id,
-        };
+        let config = NodeConfig::new(self.0.user_id, address, authorization, resume);
 
         let (node, rx) = Node::connect(config, self.0.players.clone()).await?;
-        self.0.nodes.insert(address, node.clone());
+        self.0.nodes.insert(node.address().clone(), node.clone());
 
         Ok((node, rx))
     }
 
-    /// Get a node with the socket address.
-    pub fn get(&self, address: SocketAddr) -> Option<Node> {
-        if let Some(node) = self.0.nodes.get(&address) {
-            Some(node.clone())
-        } else {
-            None
-        }
+    /// Get a node with the address.
+    pub fn get(&self, address: impl Into<NodeAddress>) -> Option<Node> {
+        self.0
+            .nodes
+            .get(&address.into())
+            .map(|node| node.value().clone())
     }
 
     /// Remove a node from the list of nodes being managed by the Lavalink
     /// client.
     ///
     /// The node is returned if it existed.
-    pub fn remove(&self, address: SocketAddr) -> Option<(SocketAddr, Node)> {
-        self.0.nodes.remove(&address)
+    pub fn remove(&self, address: impl Into<NodeAddress>) -> Option<(NodeAddress, Node)> {
+        self.0.nodes.remove(&address.into())
     }
 
-    /// Determine the "best" node for new players according to available nodes'
-    /// penalty scores.
+    /// Remove a node from the client, destroying its players and closing its
+    /// connection.
     ///
-    /// Refer to [`Node::penalty`] for how this is calculated.
+    /// A [`Destroy`] event is sent for each player on the node before the
+    /// connection is closed, and the players are removed from the player
+    /// manager.
+    ///
+    /// Returns the guild IDs of the destroyed players. If no node exists with
+    /// the address, nothing is done and an empty list is returned.
+    ///
+    /// [`Destroy`]: ../model/outgoing/struct.Destroy.html
+    pub async fn remove_node(&self, address: impl Into<NodeAddress>) -> Vec<GuildId> {
+        let node = match self.0.nodes.remove(&address.into()) {
+            Some((_, node)) => node,
+            None => return Vec::new(),
+        };
+
+        let guild_ids = self
+            .players()
+            .players
+            .iter()
+            .filter(|player| player.node().ptr_eq(&node))
+            .map(|player| player.guild_id())
+            .collect::<Vec<_>>();
+
+        for guild_id in guild_ids.iter() {
+            let _ = node.send(Destroy::new(*guild_id));
+            self.players().remove(guild_id);
+        }
+
+        node.close().await;
+
+        guild_ids
+    }
+
+    /// Move a player to another node.
+    ///
+    /// The player is destroyed on its current node. The last voice update and
+    /// track sent through the player are then sent to the new node, with the
+    /// track starting from the player's estimated position. The player's
+    /// volume and filters are also sent if they have been changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::PlayerNotFound`] if no player exists for the
+    /// guild.
+    ///
+    /// Returns [`ClientError::SendingVoiceUpdate`] or
+    /// [`ClientError::SendingEvent`] if the new node's connection was
+    /// shutdown.
+    ///
+    /// [`ClientError::PlayerNotFound`]: enum.ClientError.html#variant.PlayerNotFound
+    /// [`ClientError::SendingEvent`]: enum.ClientError.html#variant.SendingEvent
+    /// [`ClientError::SendingVoiceUpdate`]: enum.ClientError.html#variant.SendingVoiceUpdate
+    pub fn migrate_player(&self, guild_id: GuildId, to: &Node) -> Result<(), ClientError> {
+        let mut player = self
+            .players()
+            .get_mut(&guild_id)
+            .ok_or(ClientError::PlayerNotFound { guild_id })?;
+
+        let _ = player.node().send(Destroy::new(guild_id));
+        *player.node_mut() = to.clone();
+
+        for event in player.restore_events() {
+            if let OutgoingEvent::VoiceUpdate(_) = event {
+                to.send(event)
+                    .map_err(|source| ClientError::SendingVoiceUpdate { source })?;
+            } else {
+                to.send(event)
+                    .map_err(|source| ClientError::SendingEvent { source })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Determine the "best" node for new players according to the configured
+    /// [`NodeSelection`] strategy.
     ///
     /// # Errors
     ///
@@ -151,16 +436,40 @@ impl Lavalink {
     /// nodes available in the client.
     ///
     /// [`ClientError::NodesUnconfigured`]: enum.ClientError.html#variant.NodesUnconfigured
-    /// [`Node::penalty`]: ../node/struct.Node.html#method.penalty
+    /// [`NodeSelection`]: enum.NodeSelection.html
     pub async fn best(&self) -> Result<Node, ClientError> {
-        let mut lowest = i32::MAX;
+        let mut nodes = self
+            .0
+            .nodes
+            .iter()
+            .map(|node| (node.key().clone(), node.value().clone()))
+            .collect::<Vec<_>>();
+
+        if nodes.is_empty() {
+            return Err(ClientError::NodesUnconfigured);
+        }
+
+        if self.0.node_selection == NodeSelection::RoundRobin {
+            // The map's iteration order changes as nodes are added, so the
+            // nodes are taken in the order of their addresses instead.
+            nodes.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let index = self.0.round_robin.fetch_add(1, Ordering::Relaxed);
+
+            return Ok(nodes[index % nodes.len()].1.clone());
+        }
+
+        let mut lowest = u64::MAX;
         let mut best = None;
 
-        for node in self.0.nodes.iter() {
-            let penalty = node.value().penalty().await;
+        for (_, node) in nodes.iter() {
+            let score = if self.0.node_selection == NodeSelection::LeastPlayers {
+                node.stats().await.players
+            } else {
+                node.penalty().await.max(0) as u64
+            };
 
-            if penalty < lowest {
-                lowest = penalty;
+            if score < lowest {
+                lowest = score;
                 best.replace(node.clone());
             }
         }
@@ -168,11 +477,75 @@ impl Lavalink {
         best.ok_or(ClientError::NodesUnconfigured)
     }
 
+    /// Retrieve the connected node with the lowest penalty score, regardless
+    /// of the configured [`NodeSelection`] strategy.
+    ///
+    /// Nodes that are not [`ConnectionState::Connected`] are skipped. If
+    /// multiple nodes have the same penalty score, the node with the fewest
+    /// players is returned.
+    ///
+    /// Returns `None` if there are no connected nodes.
+    ///
+    /// [`ConnectionState::Connected`]: ../node/enum.ConnectionState.html#variant.Connected
+    /// [`NodeSelection`]: enum.NodeSelection.html
+    pub async fn best_node(&self) -> Option<Node> {
+        let nodes = self
+            .0
+            .nodes
+            .iter()
+            .map(|node| node.value().clone())
+            .filter(|node| node.state() == ConnectionState::Connected)
+            .collect::<Vec<_>>();
+
+        let mut lowest = (i32::MAX, u64::MAX);
+        let mut best = None;
+
+        for node in nodes {
+            let score = (node.penalty().await, node.stats().await.players);
+
+            if best.is_none() || score < lowest {
+                lowest = score;
+                best.replace(node);
+            }
+        }
+
+        best
+    }
+
     /// Retrieve an immutable reference to the player manager.
     pub fn players(&self) -> &PlayerManager {
         &self.0.players
     }
 
+    /// Retrieve an existing player for the guild, without creating one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::PlayerNotFound`] if no player exists for the
+    /// guild.
+    ///
+    /// [`ClientError::PlayerNotFound`]: enum.ClientError.html#variant.PlayerNotFound
+    pub fn existing_player(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Ref<'_, GuildId, Player>, ClientError> {
+        self.players()
+            .get(&guild_id)
+            .ok_or(ClientError::PlayerNotFound { guild_id })
+    }
+
+    /// Similar to [`existing_player`], but returns a mutable reference.
+    ///
+    /// [`existing_player`]: #method.existing_player
+    pub fn existing_player_mut(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<RefMut<'_, GuildId, Player>, ClientError> {
+        self.players()
+            .get_mut(&guild_id)
+            .ok_or(ClientError::PlayerNotFound { guild_id })
+    }
+
     /// Retrieve a player for the guild.
     ///
     /// Creates a player configured to use the best available node if a player
@@ -197,3 +570,125 @@ impl Lavalink {
         Ok(self.players().get_or_insert(guild_id, node).downgrade())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Lavalink, NodeSelection};
+    use crate::{
+        mock::{config, MockNode},
+        model::{Filters, IncomingEvent, OutgoingEvent, SetFilters, SetVolume, Timescale},
+    };
+    use async_tungstenite::tungstenite::Message;
+    use futures_util::stream::StreamExt;
+    use twilight_model::id::{GuildId, UserId};
+
+    #[tokio::test]
+    async fn test_migrated_player_ignores_old_node() {
+        let lavalink = Lavalink::new(UserId(1));
+        let (from, mut from_events, mut from_mock) =
+            MockNode::connect(config(5000), lavalink.players().clone());
+        let (to, _to_events, mut to_mock) =
+            MockNode::connect(config(5001), lavalink.players().clone());
+
+        lavalink
+            .players()
+            .get_or_insert(GuildId(2), from)
+            .play("track")
+            .send()
+            .unwrap();
+        assert!(matches!(
+            from_mock.next_outgoing().await,
+            Some(OutgoingEvent::Play(_))
+        ));
+
+        lavalink.migrate_player(GuildId(2), &to).unwrap();
+        assert!(matches!(
+            from_mock.next_outgoing().await,
+            Some(OutgoingEvent::Destroy(_))
+        ));
+        assert!(matches!(
+            to_mock.next_outgoing().await,
+            Some(OutgoingEvent::Play(_))
+        ));
+
+        // The old node's replies to the destroy.
+        from_mock
+            .push_raw(Message::Text(
+                r#"{"op":"event","type":"TrackEndEvent","guildId":"2","track":"track","reason":"CLEANUP"}"#
+                    .to_owned(),
+            ))
+            .unwrap();
+        from_mock
+            .push_raw(Message::Text(
+                r#"{"op":"event","type":"PlayerDestroyedEvent","guildId":"2","cleanup":false}"#
+                    .to_owned(),
+            ))
+            .unwrap();
+        assert!(matches!(
+            from_events.next().await,
+            Some(IncomingEvent::TrackEnd(_))
+        ));
+        assert!(matches!(
+            from_events.next().await,
+            Some(IncomingEvent::PlayerDestroy(_))
+        ));
+
+        let player = lavalink.players().get(&GuildId(2)).unwrap();
+        assert!(player.node().ptr_eq(&to));
+        assert_eq!(player.current_track(), Some("track"));
+    }
+
+    #[tokio::test]
+    async fn test_migrate_player_restores_volume_and_filters() {
+        let lavalink = Lavalink::new(UserId(1));
+        let (from, _from_events, _from_mock) =
+            MockNode::connect(config(5000), lavalink.players().clone());
+        let (to, _to_events, mut to_mock) =
+            MockNode::connect(config(5001), lavalink.players().clone());
+        let filters = Filters {
+            timescale: Some(Timescale::nightcore()),
+            ..Filters::default()
+        };
+
+        {
+            let mut player = lavalink.players().get_or_insert(GuildId(2), from);
+            player.set_volume(50).unwrap();
+            player.set_filters(filters.clone()).unwrap();
+            player.play("track").send().unwrap();
+        }
+
+        lavalink.migrate_player(GuildId(2), &to).unwrap();
+        assert_eq!(
+            to_mock.next_outgoing().await,
+            Some(SetVolume::new(GuildId(2), 50).into())
+        );
+        assert_eq!(
+            to_mock.next_outgoing().await,
+            Some(SetFilters::new(GuildId(2), filters).into())
+        );
+        assert!(matches!(
+            to_mock.next_outgoing().await,
+            Some(OutgoingEvent::Play(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_address_order() {
+        let lavalink = Lavalink::builder(UserId(1))
+            .node_selection(NodeSelection::RoundRobin)
+            .build();
+        let mut mocks = Vec::new();
+
+        for port in [5002, 5000, 5001].iter() {
+            let (node, _events, mock) =
+                MockNode::connect(config(*port), lavalink.players().clone());
+            lavalink.0.nodes.insert(node.config().address.clone(), node);
+            mocks.push(mock);
+        }
+
+        for port in [5000, 5001, 5002, 5000].iter() {
+            let node = lavalink.best().await.unwrap();
+            assert_eq!(node.config().address, config(*port).address);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Decoding of base64 track strings without a request to a node.
+//!
+//! Tracks are encoded by Lavaplayer in a binary format, which contains the
+//! title, author, length, identifier, and more of the track. Decoding them
+//! locally avoids a round-trip to the [decodetrack endpoint].
+//!
+//! [decodetrack endpoint]: ../http/fn.decode_track.html
+
+use crate::http::TrackInfo;
+use base64::DecodeError as Base64Error;
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+/// The flag in the message header indicating that the message has a version.
+const TRACK_INFO_VERSIONED: u32 = 1;
+
+/// An error that occurred while decoding a track.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The track is not valid base64.
+    Base64 {
+        /// The source of the error from the `base64` crate.
+        source: Base64Error,
+    },
+    /// The track ended before all of its fields were read.
+    UnexpectedEnd,
+    /// A string within the track is not valid modified UTF-8.
+    InvalidString,
+    /// The version of the track is not supported.
+    UnsupportedVersion {
+        /// The version of the track.
+        version: u8,
+    },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Base64 { .. } => f.write_str("track is not valid base64"),
+            Self::UnexpectedEnd => f.write_str("track ended unexpectedly"),
+            Self::InvalidString => f.write_str("track contains an invalid string"),
+            Self::UnsupportedVersion { version } => {
+                write!(f, "track version {} is not supported", version)
+            }
+        }
+    }
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Base64 { source } => Some(source),
+            Self::UnexpectedEnd => None,
+            Self::InvalidString => None,
+            Self::UnsupportedVersion { .. } => None,
+        }
+    }
+}
+
+/// Decode the information of a base64 encoded track string.
+///
+/// The track's class isn't encoded, so it is left empty. Whether the track is
+/// seekable is assumed from whether it is a stream.
+///
+/// # Errors
+///
+/// Returns [`DecodeError::Base64`] if the track is not valid base64.
+///
+/// Returns [`DecodeError::UnexpectedEnd`] if the track is truncated.
+///
+/// Returns [`DecodeError::InvalidString`] if a string within the track is
+/// invalid.
+///
+/// Returns [`DecodeError::UnsupportedVersion`] if the track was encoded with
+/// an unknown version.
+///
+/// [`DecodeError::Base64`]: enum.DecodeError.html#variant.Base64
+/// [`DecodeError::InvalidString`]: enum.DecodeError.html#variant.InvalidString
+/// [`DecodeError::UnexpectedEnd`]: enum.DecodeError.html#variant.UnexpectedEnd
+/// [`DecodeError::UnsupportedVersion`]: enum.DecodeError.html#variant.UnsupportedVersion
+pub fn decode_track_blob(track: &str) -> Result<TrackInfo, DecodeError> {
+    let bytes = base64::decode(track).map_err(|source| DecodeError::Base64 { source })?;
+    let mut reader = Reader::new(&bytes);
+
+    let header = reader.read_u32()?;
+    let flags = header >> 30;
+    let size = (header & 0x3FFF_FFFF) as usize;
+
+    // The message is limited to its size, with the position being the last
+    // field after any data specific to the source.
+    let mut reader = Reader::new(reader.read_bytes(size)?);
+    let mut end = Reader::new(&reader.bytes[size.saturating_sub(8)..]);
+    let position = end.read_i64()?;
+
+    let version = if flags & TRACK_INFO_VERSIONED != 0 {
+        reader.read_u8()?
+    } else {
+        1
+    };
+
+    if !(1..=3).contains(&version) {
+        return Err(DecodeError::UnsupportedVersion { version });
+    }
+
+    let title = reader.read_utf()?;
+    let author = reader.read_utf()?;
+    let length = reader.read_i64()?;
+    let identifier = reader.read_utf()?;
+    let is_stream = reader.read_bool()?;
+    let uri = if version >= 2 {
+        reader.read_nullable_utf()?
+    } else {
+        None
+    };
+
+    Ok(TrackInfo {
+        class: String::new(),
+        title,
+        author,
+        length: length as u64,
+        identifier,
+        uri: uri.unwrap_or_default(),
+        is_stream,
+        is_seekable: !is_stream,
+        position: position as u64,
+    })
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        self.offset += len;
+
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_bool(&mut self) -> Result<bool, DecodeError> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, DecodeError> {
+        let bytes = self.read_bytes(2)?;
+
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        let bytes = self.read_bytes(4)?;
+
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, DecodeError> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.read_bytes(8)?);
+
+        Ok(i64::from_be_bytes(buf))
+    }
+
+    fn read_nullable_utf(&mut self) -> Result<Option<String>, DecodeError> {
+        if self.read_bool()? {
+            self.read_utf().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read a string encoded as modified UTF-8, as written by Java's
+    /// `DataOutput::writeUTF`.
+    fn read_utf(&mut self) -> Result<String, DecodeError> {
+        let len = self.read_u16()? as usize;
+        let bytes = self.read_bytes(len)?;
+
+        let mut units = Vec::with_capacity(len);
+        let mut iter = bytes.iter().map(|byte| u16::from(*byte));
+
+        while let Some(a) = iter.next() {
+            let mut next = || {
+                iter.next()
+                    .filter(|byte| byte & 0xC0 == 0x80)
+                    .ok_or(DecodeError::InvalidString)
+            };
+
+            let unit = match a >> 4 {
+                0..=7 => a,
+                12 | 13 => ((a & 0x1F) << 6) | (next()? & 0x3F),
+                14 => ((a & 0x0F) << 12) | ((next()? & 0x3F) << 6) | (next()? & 0x3F),
+                _ => return Err(DecodeError::InvalidString),
+            };
+
+            units.push(unit);
+        }
+
+        String::from_utf16(&units).map_err(|_| DecodeError::InvalidString)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_track_blob, DecodeError};
+    use crate::http::TrackInfo;
+
+    /// A version 2 track encoded by Lavaplayer.
+    const TRACK_V2: &str = "QAAAjQIAJVJpY2sgQXN0bGV5IC0gTmV2ZXIgR29ubmEgR2l2ZSBZb3UgVXAADlJpY2tBc3RsZXlWRVZPAAAAAAADPCAAC2RRdzR3OVdnWGNRAAEAK2h0dHBzOi8vd3d3LnlvdXR1YmUuY29tL3dhdGNoP3Y9ZFF3NHc5V2dYY1EAB3lvdXR1YmUAAAAAAAAAAA==";
+
+    fn info(title: &str, uri: &str, position: u64) -> TrackInfo {
+        TrackInfo {
+            class: String::new(),
+            title: title.to_owned(),
+            author: "Rick Astley".to_owned(),
+            length: 212_000,
+            identifier: "dQw4w9WgXcQ".to_owned(),
+            uri: uri.to_owned(),
+            is_stream: false,
+            is_seekable: true,
+            position,
+        }
+    }
+
+    #[test]
+    fn test_decode_version_1() {
+        let track = "AAAATQAXTmV2ZXIgR29ubmEgR2l2ZSBZb3UgVXAAC1JpY2sgQXN0bGV5AAAAAAADPCAAC2RRdzR3OVdnWGNRAAAHeW91dHViZQAAAAAAAAAA";
+
+        assert_eq!(
+            decode_track_blob(track).unwrap(),
+            info("Never Gonna Give You Up", "", 0)
+        );
+    }
+
+    #[test]
+    fn test_decode_version_2() {
+        let mut expected = info(
+            "Rick Astley - Never Gonna Give You Up",
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            0,
+        );
+        expected.author = "RickAstleyVEVO".to_owned();
+
+        assert_eq!(decode_track_blob(TRACK_V2).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_decode_version_3() {
+        // The artwork URL and ISRC of version 3 are skipped.
+        let track = "QAAAwgMAF05ldmVyIEdvbm5hIEdpdmUgWW91IFVwAAtSaWNrIEFzdGxleQAAAAAAAzwgAAtkUXc0dzlXZ1hjUQABACtodHRwczovL3d3dy55b3V0dWJlLmNvbS93YXRjaD92PWRRdzR3OVdnWGNRAQA0aHR0cHM6Ly9pLnl0aW1nLmNvbS92aS9kUXc0dzlXZ1hjUS9tYXhyZXNkZWZhdWx0LmpwZwEADEdCQVJMOTMwMDEzNQAHeW91dHViZQAAAAAAAHUw";
+
+        assert_eq!(
+            decode_track_blob(track).unwrap(),
+            info(
+                "Never Gonna Give You Up",
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+                30_000
+            )
+        );
+    }
+
+    #[test]
+    fn test_decode_null_uri() {
+        let track = "QAAAQAIACkxvZmkgUmFkaW8ACUxvZmkgR2lybH//////////AAtqZktmUGZ5SlJkawEAAAd5b3V0dWJlAAAAAAAAAAA=";
+        let info = decode_track_blob(track).unwrap();
+
+        assert_eq!(info.uri, "");
+        assert!(info.is_stream);
+        assert!(!info.is_seekable);
+        assert_eq!(info.length, i64::MAX as u64);
+    }
+
+    #[test]
+    fn test_decode_modified_utf8() {
+        // Two and three byte sequences, a surrogate pair, and an encoded nul.
+        let track = "QAAAUgIAFkNhZsOpIOaXpeacrCDtoLztvrUgwIAABkFydGlzdAAAAAAAAAPoAAJpZAABABNodHRwczovL2V4YW1wbGUuY29tAARodHRwAAAAAAAAAAA=";
+
+        assert_eq!(
+            decode_track_blob(track).unwrap().title,
+            "Café 日本 \u{1F3B5} \u{0}"
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(matches!(
+            decode_track_blob("not base64!"),
+            Err(DecodeError::Base64 { .. })
+        ));
+        assert!(matches!(
+            decode_track_blob(&TRACK_V2[..80]),
+            Err(DecodeError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            decode_track_blob("QAAACQQAAAAAAAAAAA=="),
+            Err(DecodeError::UnsupportedVersion { version: 4 })
+        ));
+        // A continuation byte without a leading byte.
+        assert!(matches!(
+            decode_track_blob("QAAADAIAAYAAAAAAAAAAAA=="),
+            Err(DecodeError::InvalidString)
+        ));
+    }
+}
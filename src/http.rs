@@ -1,20 +1,73 @@
 //! Models to deserialize responses into and functions to create `http` crate
 //! requests.
 
-use crate::node::NodeConfig;
+use crate::{
+    model::{PlayerUpdateState, Stats},
+    node::NodeConfig,
+};
 use http::{
-    header::{HeaderValue, AUTHORIZATION},
+    header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE},
     Error as HttpError, Request,
 };
 use percent_encoding::NON_ALPHANUMERIC;
-use serde::{Deserialize, Serialize};
+use reqwest::{Body, Client, Error as ReqwestError};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Error as JsonError;
+use std::{
+    convert::TryInto,
+    error::Error as StdError,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
 use twilight_model::id::GuildId;
 
+/// An error that occurred while executing a request to a node.
+#[derive(Debug)]
+pub enum RequestError {
+    /// Building the HTTP request failed.
+    BuildingRequest {
+        /// The source of the error from the `http` crate.
+        source: HttpError,
+    },
+    /// Executing the HTTP request failed.
+    ExecutingRequest {
+        /// The source of the error from the `reqwest` crate.
+        source: ReqwestError,
+    },
+    /// Deserializing the response body failed.
+    DeserializingResponse {
+        /// The source of the error from the `serde_json` crate.
+        source: JsonError,
+    },
+}
+
+impl Display for RequestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::BuildingRequest { .. } => f.write_str("failed to build http request"),
+            Self::ExecutingRequest { .. } => f.write_str("failed to execute http request"),
+            Self::DeserializingResponse { .. } => {
+                f.write_str("failed to deserialize response body")
+            }
+        }
+    }
+}
+
+impl StdError for RequestError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::BuildingRequest { source } => Some(source),
+            Self::ExecutingRequest { source } => Some(source),
+            Self::DeserializingResponse { source } => Some(source),
+        }
+    }
+}
+
 /// The type of search result given.
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LoadType {
     /// Loading the results failed.
+    #[default]
     LoadFailed,
     /// There were no matches.
     NoMatches,
@@ -26,12 +79,6 @@ pub enum LoadType {
     TrackLoaded,
 }
 
-impl Default for LoadType {
-    fn default() -> Self {
-        Self::LoadFailed
-    }
-}
-
 /// A track within a search result.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -92,7 +139,7 @@ pub struct LoadedTracks {
     /// Error that happened while loading track.
     pub cause: Option<Error>,
     /// Severity of the error.
-    pub severity: Option<String>,
+    pub severity: Option<ExceptionSeverity>,
 }
 
 /// Error information.
@@ -103,17 +150,206 @@ pub struct Error {
     pub class: String,
     /// Message of the error.
     pub message: Option<String>,
+    /// Severity of the error, if it was reported by Lavaplayer.
+    #[serde(default)]
+    pub severity: Option<ExceptionSeverity>,
     /// Stack trace of the error, always None.
     #[serde(skip)]
     pub stack: Option<String>,
-    /// Cause of the error, always None.
-    #[serde(skip)]
-    pub cause: Option<String>,
+    /// The error that caused this error, if any.
+    #[serde(default)]
+    pub cause: Option<Box<Error>>,
     /// Suppressed errors, always None.
     #[serde(skip)]
     pub suppressed: Option<String>,
 }
 
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.message.as_ref() {
+            Some(message) => write!(f, "{}: {}", self.class, message),
+            None => f.write_str(&self.class),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.cause
+            .as_deref()
+            .map(|cause| cause as &(dyn StdError + 'static))
+    }
+}
+
+/// How severe an error is, which determines whether retrying may help.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExceptionSeverity {
+    /// The cause is known and expected, such as a track being unavailable or
+    /// age restricted.
+    ///
+    /// Retrying the same track won't help, but trying another source may.
+    Common,
+    /// The cause might not be exactly known, but is possibly caused by outside
+    /// factors, such as the source of the track changing.
+    ///
+    /// Retrying may help.
+    Suspicious,
+    /// The probable cause is an issue with the node or Lavaplayer.
+    ///
+    /// Retrying is unlikely to help.
+    Fault,
+}
+
+/// A player on a node, as returned when retrieving every player.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerInfo {
+    /// The guild ID of the player.
+    pub guild_id: GuildId,
+    /// The state of the player.
+    #[serde(flatten)]
+    pub state: PlayerUpdateState,
+}
+
+/// Information about the software of a node.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfo {
+    /// The versions of the node's software.
+    pub versions: NodeVersions,
+    /// The names of the plugins loaded by the node.
+    #[serde(default)]
+    pub plugins: Vec<String>,
+    /// The names of the source managers enabled on the node.
+    #[serde(default)]
+    pub source_managers: Vec<String>,
+}
+
+/// The versions of a node's software.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeVersions {
+    /// The version of Andesite, such as `0.20.2`.
+    pub andesite: String,
+    /// The version of Lavaplayer.
+    pub lavaplayer: Option<String>,
+    /// The version of the JVM.
+    pub jvm: Option<String>,
+}
+
+impl NodeVersions {
+    /// Parse the major, minor and patch numbers of the Andesite version.
+    ///
+    /// Returns `None` if the version isn't in the form `major.minor.patch`.
+    /// Anything after the patch number, such as `-SNAPSHOT`, is ignored.
+    pub fn andesite_version(&self) -> Option<(u32, u32, u32)> {
+        let mut parts = self.andesite.splitn(3, '.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts
+            .next()?
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+
+        Some((major, minor, patch))
+    }
+}
+
+/// The status of the route planner of a node.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePlannerStatus {
+    /// The class name of the route planner, if one is configured.
+    pub class: Option<String>,
+    /// Details about the route planner, if one is configured.
+    pub details: Option<RoutePlannerDetails>,
+}
+
+/// Details about the route planner of a node.
+///
+/// Which of the indexes are present depends on the class of the route planner.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePlannerDetails {
+    /// The IP block being used.
+    pub ip_block: IpBlock,
+    /// The addresses that are failing.
+    pub failing_addresses: Vec<FailingAddress>,
+    /// The number of rotations.
+    pub rotate_index: Option<String>,
+    /// The current offset in the IP block.
+    pub ip_index: Option<String>,
+    /// The current address being used.
+    pub current_address: Option<String>,
+    /// The current offset in the IP block.
+    pub current_address_index: Option<String>,
+    /// The index of the current /64 block.
+    pub block_index: Option<String>,
+}
+
+/// An IP block used by a route planner.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpBlock {
+    /// The type of the IP block, such as `Inet4Address` or `Inet6Address`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The size of the IP block.
+    pub size: String,
+}
+
+/// An address that is failing and not being used by a route planner.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailingAddress {
+    /// The address that is failing.
+    #[serde(alias = "failingAddress")]
+    pub address: String,
+    /// The timestamp in milliseconds when the address failed.
+    pub failing_timestamp: u64,
+    /// The time when the address failed, formatted as a string.
+    pub failing_time: String,
+}
+
+/// An identifier for searching tracks, prefixed with the source to search.
+///
+/// This can be used as the identifier when loading tracks, as can a raw
+/// identifier string.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Search(String);
+
+impl Search {
+    /// Create an identifier searching YouTube for a query.
+    pub fn youtube(query: impl AsRef<str>) -> Self {
+        Self(format!("ytsearch:{}", query.as_ref()))
+    }
+
+    /// Create an identifier searching SoundCloud for a query.
+    pub fn soundcloud(query: impl AsRef<str>) -> Self {
+        Self(format!("scsearch:{}", query.as_ref()))
+    }
+
+    /// Consume the search, returning the identifier.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl AsRef<str> for Search {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Search {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.0)
+    }
+}
+
 /// Get a list of tracks that match an identifier.
 ///
 /// The response will include a body which can be deserialized into a
@@ -127,8 +363,10 @@ pub fn load_track(
     let identifier =
         percent_encoding::percent_encode(identifier.as_ref().as_bytes(), NON_ALPHANUMERIC);
     let url = format!(
-        "http://{}/loadtracks?identifier={}",
-        config.address, identifier
+        "{}://{}/loadtracks?identifier={}",
+        config.http_scheme(),
+        config.address,
+        identifier
     );
 
     let mut req = Request::get(url);
@@ -139,15 +377,53 @@ pub fn load_track(
     req.body(b"")
 }
 
+/// Get a list of tracks that match an identifier, executing the request with
+/// the given client.
+///
+/// Refer to [`load_track`] for the request that is made.
+///
+/// [`load_track`]: fn.load_track.html
+pub async fn load_tracks(
+    client: &Client,
+    config: NodeConfig,
+    identifier: impl AsRef<str>,
+) -> Result<LoadedTracks, RequestError> {
+    let req = load_track(config, identifier)
+        .map_err(|source| RequestError::BuildingRequest { source })?;
+
+    execute(client, req).await
+}
+
+async fn execute<T: DeserializeOwned>(
+    client: &Client,
+    req: Request<impl Into<Body>>,
+) -> Result<T, RequestError> {
+    let req = req
+        .try_into()
+        .map_err(|source| RequestError::ExecutingRequest { source })?;
+    let bytes = client
+        .execute(req)
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|source| RequestError::ExecutingRequest { source })?
+        .bytes()
+        .await
+        .map_err(|source| RequestError::ExecutingRequest { source })?;
+
+    serde_json::from_slice(&bytes).map_err(|source| RequestError::DeserializingResponse { source })
+}
+
 /// Decode a track based on the base64 encoded track string.
 pub fn decode_track(
     config: NodeConfig,
     track: impl AsRef<str>,
 ) -> Result<Request<&'static [u8]>, HttpError> {
+    let track = percent_encoding::percent_encode(track.as_ref().as_bytes(), NON_ALPHANUMERIC);
     let url = format!(
-        "http://{}/decodetrack?track={}",
+        "{}://{}/decodetrack?track={}",
+        config.http_scheme(),
         config.address,
-        track.as_ref()
+        track
     );
 
     let mut req = Request::get(url);
@@ -158,9 +434,69 @@ pub fn decode_track(
     req.body(b"")
 }
 
+/// Decode a track based on the base64 encoded track string, executing the
+/// request with the given client.
+///
+/// Refer to [`decode_track`] for the request that is made.
+///
+/// [`decode_track`]: fn.decode_track.html
+pub async fn decode_track_info(
+    client: &Client,
+    config: NodeConfig,
+    track: impl AsRef<str>,
+) -> Result<TrackInfo, RequestError> {
+    let req =
+        decode_track(config, track).map_err(|source| RequestError::BuildingRequest { source })?;
+
+    execute(client, req).await
+}
+
+/// Decode multiple tracks based on their base64 encoded track strings.
+///
+/// The response will include a body which can be deserialized into a list of
+/// [`Track`]s.
+///
+/// [`Track`]: struct.Track.html
+pub fn decode_tracks(config: NodeConfig, tracks: &[String]) -> Result<Request<Vec<u8>>, HttpError> {
+    let url = format!("{}://{}/decodetracks", config.http_scheme(), config.address);
+    let body = serde_json::Value::from(tracks.to_vec())
+        .to_string()
+        .into_bytes();
+
+    let mut req = Request::post(url);
+
+    let auth_value = HeaderValue::from_str(config.authorization.as_ref())?;
+    req = req.header(AUTHORIZATION, auth_value);
+    req = req.header(CONTENT_TYPE, "application/json");
+
+    req.body(body)
+}
+
+/// Decode multiple tracks based on their base64 encoded track strings,
+/// executing the request with the given client.
+///
+/// Refer to [`decode_tracks`] for the request that is made.
+///
+/// [`decode_tracks`]: fn.decode_tracks.html
+pub async fn decode_tracks_info(
+    client: &Client,
+    config: NodeConfig,
+    tracks: &[String],
+) -> Result<Vec<Track>, RequestError> {
+    let req =
+        decode_tracks(config, tracks).map_err(|source| RequestError::BuildingRequest { source })?;
+
+    execute(client, req).await
+}
+
 /// Retrieve a player based on guild ID.
 pub fn get_player(config: NodeConfig, guild: GuildId) -> Result<Request<&'static [u8]>, HttpError> {
-    let url = format!("http://{}/player/{}", config.address, guild);
+    let url = format!(
+        "{}://{}/player/{}",
+        config.http_scheme(),
+        config.address,
+        guild
+    );
 
     let mut req = Request::get(url);
 
@@ -170,3 +506,431 @@ pub fn get_player(config: NodeConfig, guild: GuildId) -> Result<Request<&'static
 
     req.body(b"")
 }
+
+/// Retrieve every player on the node for the user.
+///
+/// The response will include a body which can be deserialized into a `Vec` of
+/// [`PlayerInfo`].
+///
+/// [`PlayerInfo`]: struct.PlayerInfo.html
+pub fn get_players(config: NodeConfig) -> Result<Request<&'static [u8]>, HttpError> {
+    let url = format!("{}://{}/players", config.http_scheme(), config.address);
+
+    let mut req = Request::get(url);
+
+    let auth_value = HeaderValue::from_str(config.authorization.as_ref())?;
+    req = req.header(AUTHORIZATION, auth_value);
+    req = req.header("User-Id", config.user_id.to_string());
+
+    req.body(b"")
+}
+
+/// Retrieve every player on the node for the user, executing the request with
+/// the given client.
+///
+/// This can be used to rebuild the players of a [`PlayerManager`] after a
+/// restart. Refer to [`get_players`] for the request that is made.
+///
+/// [`PlayerManager`]: ../player/struct.PlayerManager.html
+/// [`get_players`]: fn.get_players.html
+pub async fn get_players_info(
+    client: &Client,
+    config: NodeConfig,
+) -> Result<Vec<PlayerInfo>, RequestError> {
+    let req = get_players(config).map_err(|source| RequestError::BuildingRequest { source })?;
+
+    execute(client, req).await
+}
+
+/// Retrieve the current statistics of the node.
+///
+/// The response will include a body which can be deserialized into a
+/// [`Stats`].
+///
+/// [`Stats`]: ../model/incoming/struct.Stats.html
+pub fn get_stats(config: NodeConfig) -> Result<Request<&'static [u8]>, HttpError> {
+    let url = format!(
+        "{}://{}/stats/lavalink",
+        config.http_scheme(),
+        config.address
+    );
+
+    let mut req = Request::get(url);
+
+    let auth_value = HeaderValue::from_str(config.authorization.as_ref())?;
+    req = req.header(AUTHORIZATION, auth_value);
+
+    req.body(b"")
+}
+
+/// Retrieve the current statistics of the node, executing the request with
+/// the given client.
+///
+/// Unlike [`Node::stats`], which returns the statistics last sent by the node
+/// over the websocket, this retrieves them on demand. Refer to [`get_stats`]
+/// for the request that is made.
+///
+/// [`Node::stats`]: ../node/struct.Node.html#method.stats
+/// [`get_stats`]: fn.get_stats.html
+pub async fn get_stats_info(client: &Client, config: NodeConfig) -> Result<Stats, RequestError> {
+    let req = get_stats(config).map_err(|source| RequestError::BuildingRequest { source })?;
+
+    execute(client, req).await
+}
+
+/// Retrieve information about the software of the node.
+///
+/// The response will include a body which can be deserialized into a
+/// [`NodeInfo`].
+///
+/// [`NodeInfo`]: struct.NodeInfo.html
+pub fn get_node(config: NodeConfig) -> Result<Request<&'static [u8]>, HttpError> {
+    let url = format!("{}://{}/stats", config.http_scheme(), config.address);
+
+    let mut req = Request::get(url);
+
+    let auth_value = HeaderValue::from_str(config.authorization.as_ref())?;
+    req = req.header(AUTHORIZATION, auth_value);
+
+    req.body(b"")
+}
+
+/// Retrieve information about the software of the node, executing the request
+/// with the given client.
+///
+/// This can be used to only use features which the node supports. Refer to
+/// [`get_node`] for the request that is made.
+///
+/// # Examples
+///
+/// Only use a filter if the node is recent enough to support it:
+///
+/// ```rust,no_run
+/// use reqwest::Client;
+/// use twilight_andesite::{
+///     http,
+///     model::{Filters, Rotation},
+///     node::NodeConfig,
+///     player::Player,
+/// };
+/// # async fn run(config: NodeConfig, player: &mut Player) -> Result<(), Box<dyn std::error::Error>> {
+///
+/// let info = http::get_node_info(&Client::new(), config).await?;
+///
+/// let mut filters = Filters::builder();
+///
+/// if info.versions.andesite_version() >= Some((0, 20, 0)) {
+///     filters = filters.rotation(Rotation::new(0.2));
+/// }
+///
+/// player.set_filters(filters.build())?;
+/// # Ok(()) }
+/// ```
+///
+/// [`get_node`]: fn.get_node.html
+pub async fn get_node_info(client: &Client, config: NodeConfig) -> Result<NodeInfo, RequestError> {
+    let req = get_node(config).map_err(|source| RequestError::BuildingRequest { source })?;
+
+    execute(client, req).await
+}
+
+/// Retrieve the status of the route planner.
+///
+/// The response will include a body which can be deserialized into a
+/// [`RoutePlannerStatus`].
+///
+/// [`RoutePlannerStatus`]: struct.RoutePlannerStatus.html
+pub fn route_planner_status(config: NodeConfig) -> Result<Request<&'static [u8]>, HttpError> {
+    let url = format!(
+        "{}://{}/routeplanner/status",
+        config.http_scheme(),
+        config.address
+    );
+
+    let mut req = Request::get(url);
+
+    let auth_value = HeaderValue::from_str(config.authorization.as_ref())?;
+    req = req.header(AUTHORIZATION, auth_value);
+
+    req.body(b"")
+}
+
+/// Unmark a failing address so that the route planner uses it again.
+pub fn route_planner_free_address(
+    config: NodeConfig,
+    address: impl AsRef<str>,
+) -> Result<Request<Vec<u8>>, HttpError> {
+    let url = format!(
+        "{}://{}/routeplanner/free/address",
+        config.http_scheme(),
+        config.address
+    );
+    let body = serde_json::json!({ "address": address.as_ref() })
+        .to_string()
+        .into_bytes();
+
+    let mut req = Request::post(url);
+
+    let auth_value = HeaderValue::from_str(config.authorization.as_ref())?;
+    req = req.header(AUTHORIZATION, auth_value);
+    req = req.header(CONTENT_TYPE, "application/json");
+
+    req.body(body)
+}
+
+/// Unmark all failing addresses so that the route planner uses them again.
+pub fn route_planner_free_all(config: NodeConfig) -> Result<Request<&'static [u8]>, HttpError> {
+    let url = format!(
+        "{}://{}/routeplanner/free/all",
+        config.http_scheme(),
+        config.address
+    );
+
+    let mut req = Request::post(url);
+
+    let auth_value = HeaderValue::from_str(config.authorization.as_ref())?;
+    req = req.header(AUTHORIZATION, auth_value);
+
+    req.body(b"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_tracks, Error, ExceptionSeverity, LoadType, LoadedTracks, PlayerInfo, PlaylistInfo,
+        Track,
+    };
+    use crate::node::NodeConfig;
+    use http::header::AUTHORIZATION;
+    use serde_json::{json, Value};
+    use std::error::Error as StdError;
+    use twilight_model::id::{GuildId, UserId};
+
+    fn track(identifier: &str) -> Value {
+        json!({
+            "track": "QAAAjQIAJVJpY2sgQXN0bGV5IC0gTmV2ZXIgR29ubmEgR2l2ZSBZb3UgVXAADlJpY2tBc3RsZXlWRVZPAAAAAAADPCAAC2RRdzR3OVdnWGNRAAEAK2h0dHBzOi8vd3d3LnlvdXR1YmUuY29tL3dhdGNoP3Y9ZFF3NHc5V2dYY1EAB3lvdXR1YmUAAAAAAAAAAA==",
+            "info": {
+                "class": "com.sedmelluq.discord.lavaplayer.source.youtube.YoutubeAudioTrack",
+                "title": "Rick Astley - Never Gonna Give You Up",
+                "author": "RickAstleyVEVO",
+                "length": 212000,
+                "identifier": identifier,
+                "uri": format!("https://www.youtube.com/watch?v={}", identifier),
+                "isStream": false,
+                "isSeekable": true,
+                "position": 0,
+            },
+        })
+    }
+
+    #[test]
+    fn test_loaded_tracks_track_loaded() {
+        let loaded = serde_json::from_value::<LoadedTracks>(json!({
+            "loadType": "TRACK_LOADED",
+            "tracks": [track("dQw4w9WgXcQ")],
+            "playlistInfo": null,
+            "cause": null,
+            "severity": null,
+        }))
+        .unwrap();
+
+        assert_eq!(loaded.load_type, LoadType::TrackLoaded);
+        let tracks = loaded.tracks.unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].info.identifier, "dQw4w9WgXcQ");
+        assert_eq!(tracks[0].info.length, 212_000);
+        assert!(tracks[0].info.is_seekable);
+        assert!(!tracks[0].info.is_stream);
+    }
+
+    #[test]
+    fn test_loaded_tracks_playlist_loaded() {
+        let loaded = serde_json::from_value::<LoadedTracks>(json!({
+            "loadType": "PLAYLIST_LOADED",
+            "tracks": [track("dQw4w9WgXcQ"), track("yPYZpwSpKmA")],
+            "playlistInfo": {
+                "name": "Rick Astley",
+                "selectedTrack": 1,
+            },
+            "cause": null,
+            "severity": null,
+        }))
+        .unwrap();
+
+        assert_eq!(loaded.load_type, LoadType::PlaylistLoaded);
+        assert_eq!(loaded.tracks.map(|tracks| tracks.len()), Some(2));
+        assert_eq!(
+            loaded.playlist_info,
+            Some(PlaylistInfo {
+                name: Some("Rick Astley".to_owned()),
+                selected_track: Some(1),
+            })
+        );
+    }
+
+    #[test]
+    fn test_loaded_tracks_search_result() {
+        let loaded = serde_json::from_value::<LoadedTracks>(json!({
+            "loadType": "SEARCH_RESULT",
+            "tracks": [track("dQw4w9WgXcQ"), track("yPYZpwSpKmA")],
+            "playlistInfo": null,
+            "cause": null,
+            "severity": null,
+        }))
+        .unwrap();
+
+        assert_eq!(loaded.load_type, LoadType::SearchResult);
+        let identifiers = loaded
+            .tracks
+            .unwrap()
+            .into_iter()
+            .map(|track: Track| track.info.identifier)
+            .collect::<Vec<_>>();
+        assert_eq!(identifiers, ["dQw4w9WgXcQ", "yPYZpwSpKmA"]);
+    }
+
+    #[test]
+    fn test_loaded_tracks_no_matches() {
+        let loaded = serde_json::from_value::<LoadedTracks>(json!({
+            "loadType": "NO_MATCHES",
+            "tracks": [],
+            "playlistInfo": null,
+            "cause": null,
+            "severity": null,
+        }))
+        .unwrap();
+
+        assert_eq!(loaded.load_type, LoadType::NoMatches);
+        assert_eq!(loaded.tracks, Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_loaded_tracks_load_failed() {
+        let loaded = serde_json::from_value::<LoadedTracks>(json!({
+            "loadType": "LOAD_FAILED",
+            "tracks": null,
+            "playlistInfo": null,
+            "cause": {
+                "class": "com.sedmelluq.discord.lavaplayer.tools.FriendlyException",
+                "message": "This video is unavailable",
+                "severity": "COMMON",
+                "cause": null,
+            },
+            "severity": "COMMON",
+        }))
+        .unwrap();
+
+        assert_eq!(loaded.load_type, LoadType::LoadFailed);
+        assert!(loaded.tracks.is_none());
+        assert_eq!(
+            loaded.cause.and_then(|cause| cause.message).as_deref(),
+            Some("This video is unavailable")
+        );
+        assert_eq!(loaded.severity, Some(ExceptionSeverity::Common));
+    }
+
+    #[test]
+    fn test_decode_tracks_batch() {
+        let config = NodeConfig::new(UserId(1), ([127, 0, 0, 1], 2333), "password", None);
+        let tracks = ["first".to_owned(), "second".to_owned()];
+        let req = decode_tracks(config, &tracks).unwrap();
+
+        assert_eq!(req.uri(), "http://127.0.0.1:2333/decodetracks");
+        assert_eq!(req.headers()[AUTHORIZATION], "password");
+        assert_eq!(
+            serde_json::from_slice::<Value>(req.body()).unwrap(),
+            json!(["first", "second"])
+        );
+
+        let decoded = serde_json::from_value::<Vec<Track>>(json!([
+            track("dQw4w9WgXcQ"),
+            track("yPYZpwSpKmA")
+        ]))
+        .unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1].info.identifier, "yPYZpwSpKmA");
+    }
+
+    #[test]
+    fn test_player_info() {
+        let players = serde_json::from_value::<Vec<PlayerInfo>>(json!([
+            {
+                "guildId": "1",
+                "time": 1_500_000_000_000_i64,
+                "position": 30000,
+                "paused": false,
+                "volume": 100,
+                "filters": {},
+                "mixer": {},
+                "mixerEnabled": false,
+                "frame": {
+                    "loss": 0,
+                    "success": 3000,
+                    "usable": true,
+                },
+            },
+            {
+                "guildId": "2",
+                "time": 1_500_000_000_000_i64,
+                "position": null,
+                "paused": true,
+                "volume": 50,
+                "filters": {},
+            },
+        ]))
+        .unwrap();
+
+        assert_eq!(players.len(), 2);
+        assert_eq!(players[0].guild_id, GuildId(1));
+        assert_eq!(players[0].state.position, Some(30000));
+        assert_eq!(players[1].guild_id, GuildId(2));
+        assert_eq!(players[1].state.position, None);
+        assert!(players[1].state.paused);
+        assert_eq!(players[1].state.volume, 50);
+    }
+
+    #[test]
+    fn test_exception_severity() {
+        let severities = [
+            ("COMMON", ExceptionSeverity::Common),
+            ("SUSPICIOUS", ExceptionSeverity::Suspicious),
+            ("FAULT", ExceptionSeverity::Fault),
+        ];
+
+        for (raw, severity) in severities.iter() {
+            assert_eq!(
+                serde_json::from_value::<ExceptionSeverity>(json!(raw)).unwrap(),
+                *severity
+            );
+        }
+    }
+
+    #[test]
+    fn test_exception_cause() {
+        let error = serde_json::from_value::<Error>(json!({
+            "class": "com.sedmelluq.discord.lavaplayer.tools.FriendlyException",
+            "message": "Something broke when playing the track.",
+            "severity": "FAULT",
+            "cause": {
+                "class": "java.lang.IllegalStateException",
+                "message": "Failed to read",
+                "cause": null,
+            },
+        }))
+        .unwrap();
+
+        assert_eq!(error.severity, Some(ExceptionSeverity::Fault));
+        let cause = error.cause.as_deref().unwrap();
+        assert_eq!(cause.class, "java.lang.IllegalStateException");
+        assert_eq!(cause.severity, None);
+        assert!(StdError::source(&error).is_some());
+
+        let error = serde_json::from_value::<Error>(json!({
+            "class": "java.lang.IllegalStateException",
+            "message": null,
+        }))
+        .unwrap();
+        assert_eq!(error.severity, None);
+        assert!(error.cause.is_none());
+    }
+}
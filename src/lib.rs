@@ -20,16 +20,30 @@
 //! The `http-support` feature adds support for the `http` module to return
 //! request types from the [`http`] crate. This is enabled by default.
 //!
+//! ### `mock`
+//!
+//! The `mock` feature adds the `mock` module, which contains an in-memory node
+//! for testing code which uses nodes without a Lavalink server.
+//!
 //! ### TLS
 //!
-//! `twilight-lavalink` has features to enable [`async-tungstenite`]'s TLS
-//! features. These features are mutually exclusive. `rustls` is enabled by
-//! default.
+//! `twilight-lavalink` has features to select the TLS backend used by both
+//! the websocket connection, through [`async-tungstenite`], and HTTP requests,
+//! through [`reqwest`]. These features are mutually exclusive. `rustls` is
+//! enabled by default.
+//!
+//! | Feature  | Websocket          | HTTP         | Backend    |
+//! |----------|--------------------|--------------|------------|
+//! | `native` | `tokio-native-tls` | `native-tls` | OpenSSL, SChannel, or Security Framework |
+//! | `rustls` | `tokio-rustls`     | `rustls-tls` | [`rustls`] |
+//!
+//! Use `rustls` for pure Rust builds, such as static builds against musl.
 //!
 //! #### `native`
 //!
 //! The `native` feature enables [`async-tungstenite`]'s `tokio-native-tls`
-//! feature.
+//! feature and [`reqwest`]'s `native-tls` feature. It's also available as
+//! `native-tls`.
 //!
 //! To enable `native`, do something like this in your `Cargo.toml`:
 //!
@@ -40,8 +54,9 @@
 //!
 //! #### `rustls`
 //!
-//! The `rustls` feature enables [`async-tungstenite`]'s `async-tls` feature, which
-//! use [`rustls`] as the TLS backend.
+//! The `rustls` feature enables [`async-tungstenite`]'s `tokio-rustls` feature
+//! and [`reqwest`]'s `rustls-tls` feature, which use [`rustls`] as the TLS
+//! backend.
 //!
 //! This is enabled by default.
 //!
@@ -99,6 +114,7 @@
 //! [Lavalink]: https://github.com/Frederikam/Lavalink
 //! [`async-tungstenite`]: https://crates.io/crates/async-tungstenite
 //! [`http`]: https://crates.io/crates/http
+//! [`reqwest`]: https://crates.io/crates/reqwest
 //! [`rustls`]: https://crates.io/crates/rustls
 //! [client]: client/struct.Lavalink.html
 //! [discord badge]: https://img.shields.io/discord/745809834183753828?color=%237289DA&label=discord%20server&logo=discord&style=for-the-badge
@@ -120,9 +136,10 @@
     unused,
     warnings
 )]
-#![allow(clippy::large_enum_variant)]
+#![allow(clippy::large_enum_variant, clippy::result_large_err)]
 
 pub mod client;
+pub mod decode;
 pub mod model;
 pub mod node;
 pub mod player;
@@ -130,4 +147,7 @@ pub mod player;
 #[cfg(feature = "http")]
 pub mod http;
 
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+
 pub use self::{client::Lavalink, node::Node, player::PlayerManager};
@@ -0,0 +1,370 @@
+//! An in-memory node for testing code which uses nodes without a Lavalink
+//! server.
+//!
+//! A [`MockNode`] creates a [`Node`] whose connection is in memory, so that
+//! tests can push [`IncomingEvent`]s to the node and assert on the
+//! [`OutgoingEvent`]s sent by it and its players.
+//!
+//! This is only available with the `mock` feature.
+//!
+//! [`IncomingEvent`]: ../model/incoming/enum.IncomingEvent.html
+//! [`MockNode`]: struct.MockNode.html
+//! [`Node`]: ../node/struct.Node.html
+//! [`OutgoingEvent`]: ../model/outgoing/enum.OutgoingEvent.html
+
+use crate::{
+    model::{IncomingEvent, OutgoingEvent},
+    node::{Node, NodeConfig, NodeError, Transport},
+    player::PlayerManager,
+};
+use async_tungstenite::tungstenite::{Error as TungsteniteError, Message};
+use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures_util::{
+    future::BoxFuture,
+    sink::Sink,
+    stream::{Stream, StreamExt},
+};
+use serde_json::Error as JsonError;
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(test)]
+use twilight_model::id::UserId;
+
+/// An error that occurred while pushing a message to a mock node.
+#[derive(Debug)]
+pub enum MockNodeError {
+    /// The node's connection has ended, such as after it was closed.
+    Closed,
+    /// Serializing the event as JSON failed.
+    SerializingEvent {
+        /// The source of the error from the `serde_json` crate.
+        source: JsonError,
+    },
+}
+
+impl Display for MockNodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Closed => f.write_str("the node's connection has ended"),
+            Self::SerializingEvent { .. } => f.write_str("failed to serialize event as json"),
+        }
+    }
+}
+
+impl Error for MockNodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Closed => None,
+            Self::SerializingEvent { source } => Some(source),
+        }
+    }
+}
+
+/// The server end of an in-memory connection to a [`Node`].
+///
+/// The node reconnects to the mock when its connection is lost, such as after
+/// [`disconnect`]. Dropping the mock ends the node's connection.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::net::SocketAddr;
+/// use twilight_andesite::{
+///     mock::MockNode,
+///     model::OutgoingEvent,
+///     node::NodeConfig,
+///     PlayerManager,
+/// };
+/// use twilight_model::id::{GuildId, UserId};
+///
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let address: SocketAddr = "127.0.0.1:5000".parse()?;
+/// let config = NodeConfig::new(UserId(1), address, "password", None);
+/// let players = PlayerManager::default();
+///
+/// let (node, _events, mut mock) = MockNode::connect(config, players.clone());
+/// players.get_or_insert(GuildId(2), node).play("track").send()?;
+///
+/// assert!(matches!(mock.next_outgoing().await, Some(OutgoingEvent::Play(_))));
+/// # Ok(()) }
+/// ```
+///
+/// [`Node`]: ../node/struct.Node.html
+/// [`disconnect`]: #method.disconnect
+#[derive(Debug)]
+pub struct MockNode {
+    to_node: UnboundedSender<Message>,
+    from_node: UnboundedReceiver<Message>,
+    connections: UnboundedSender<Channels>,
+    stalled: Vec<UnboundedSender<Message>>,
+}
+
+impl MockNode {
+    /// Create a node connected to a mock instead of a Lavalink server.
+    ///
+    /// Returns the node and its receiver of incoming events, the same as
+    /// [`Node::connect`], along with the mock.
+    ///
+    /// This must be called within a Tokio runtime, since the node's connection
+    /// is spawned as a task.
+    ///
+    /// [`Node::connect`]: ../node/struct.Node.html#method.connect
+    pub fn connect(
+        config: NodeConfig,
+        players: PlayerManager,
+    ) -> (Node, UnboundedReceiver<IncomingEvent>, Self) {
+        let (to_node, rx) = mpsc::unbounded();
+        let (tx, from_node) = mpsc::unbounded();
+        let (connections, connections_rx) = mpsc::unbounded();
+
+        let transport = MockTransport {
+            rx,
+            tx,
+            connections: connections_rx,
+        };
+        let (node, events) = Node::connect_with_transport(config, players, transport, None);
+
+        (
+            node,
+            events,
+            Self {
+                to_node,
+                from_node,
+                connections,
+                stalled: Vec::new(),
+            },
+        )
+    }
+
+    /// Break the node's connection, so that its next send fails and it
+    /// reconnects to the mock.
+    ///
+    /// Messages which the node hasn't received yet are lost, and the node
+    /// doesn't resume its session.
+    pub fn disconnect(&mut self) {
+        let (to_node, rx) = mpsc::unbounded();
+        let (tx, from_node) = mpsc::unbounded();
+        let _ = self.connections.unbounded_send((rx, tx));
+
+        // Keep the old connection open for reading, like a stalled socket, so
+        // that the node notices the failure when sending.
+        self.stalled
+            .push(std::mem::replace(&mut self.to_node, to_node));
+        self.from_node = from_node;
+    }
+
+    /// Push an event to the node, as if it was sent by a Lavalink server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MockNodeError::Closed`] if the node's connection has ended.
+    ///
+    /// Returns [`MockNodeError::SerializingEvent`] if the event couldn't be
+    /// serialized.
+    ///
+    /// [`MockNodeError::Closed`]: enum.MockNodeError.html#variant.Closed
+    /// [`MockNodeError::SerializingEvent`]: enum.MockNodeError.html#variant.SerializingEvent
+    pub fn push(&self, event: impl Into<IncomingEvent>) -> Result<(), MockNodeError> {
+        let payload = serde_json::to_string(&event.into())
+            .map_err(|source| MockNodeError::SerializingEvent { source })?;
+
+        self.push_raw(Message::Text(payload))
+    }
+
+    /// Push a raw websocket message to the node, such as a close frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MockNodeError::Closed`] if the node's connection has ended.
+    ///
+    /// [`MockNodeError::Closed`]: enum.MockNodeError.html#variant.Closed
+    pub fn push_raw(&self, message: Message) -> Result<(), MockNodeError> {
+        self.to_node
+            .unbounded_send(message)
+            .map_err(|_| MockNodeError::Closed)
+    }
+
+    /// Wait for the next event sent by the node.
+    ///
+    /// Pings, pongs, and messages which aren't outgoing events are skipped.
+    /// Returns `None` once the node's connection has ended.
+    pub async fn next_outgoing(&mut self) -> Option<OutgoingEvent> {
+        while let Some(message) = self.next_raw().await {
+            if let Message::Text(text) = message {
+                if let Ok(event) = serde_json::from_str::<OutgoingEvent>(&text) {
+                    return Some(event);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Wait for the next raw websocket message sent by the node.
+    ///
+    /// Returns `None` once the node's connection has ended.
+    pub async fn next_raw(&mut self) -> Option<Message> {
+        self.from_node.next().await
+    }
+}
+
+/// The node's ends of a new in-memory connection.
+type Channels = (UnboundedReceiver<Message>, UnboundedSender<Message>);
+
+/// The node's end of an in-memory connection.
+struct MockTransport {
+    rx: UnboundedReceiver<Message>,
+    tx: UnboundedSender<Message>,
+    connections: UnboundedReceiver<Channels>,
+}
+
+impl Stream for MockTransport {
+    type Item = Result<Message, TungsteniteError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_next_unpin(cx).map(|message| message.map(Ok))
+    }
+}
+
+impl Sink<Message> for MockTransport {
+    type Error = TungsteniteError;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, message: Message) -> Result<(), Self::Error> {
+        self.tx
+            .unbounded_send(message)
+            .map_err(|_| TungsteniteError::ConnectionClosed)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.tx.close_channel();
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Transport for MockTransport {
+    /// Connect to the mock again, failing once the mock was dropped.
+    fn reconnect<'a>(
+        &'a mut self,
+        _: &'a NodeConfig,
+        _: Option<u64>,
+    ) -> BoxFuture<'a, Result<Option<u64>, NodeError>> {
+        Box::pin(async move {
+            let (rx, tx) = self.connections.next().await.ok_or(NodeError::Connecting {
+                source: TungsteniteError::ConnectionClosed,
+            })?;
+            self.rx = rx;
+            self.tx = tx;
+
+            Ok(None)
+        })
+    }
+}
+
+/// The configuration of a node connecting to a mock in tests.
+#[cfg(test)]
+pub(crate) fn config(port: u16) -> NodeConfig {
+    NodeConfig::new(UserId(1), ([127, 0, 0, 1], port), "password", None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{config, MockNode};
+    use crate::{
+        model::{IncomingEvent, OutgoingEvent, Stop},
+        node::NodeEvent,
+        player::PlayerManager,
+    };
+    use async_tungstenite::tungstenite::Message;
+    use futures_util::stream::StreamExt;
+    use twilight_model::id::GuildId;
+
+    const PLAYER_UPDATE: &str = r#"{
+        "op": "playerUpdate",
+        "guildId": "2",
+        "state": {
+            "time": 1500000000000,
+            "position": 1000,
+            "paused": false,
+            "volume": 100,
+            "filters": {}
+        }
+    }"#;
+
+    #[tokio::test]
+    async fn test_push_and_next_outgoing() {
+        let players = PlayerManager::new();
+        let (_node, mut events, mut mock) = MockNode::connect(config(5000), players.clone());
+
+        mock.push_raw(Message::Text(PLAYER_UPDATE.to_owned()))
+            .unwrap();
+        assert!(matches!(
+            events.next().await,
+            Some(IncomingEvent::PlayerUpdate(_))
+        ));
+
+        let mut player = players.get_mut(&GuildId(2)).unwrap();
+        assert_eq!(player.position(), Some(1000));
+
+        player.play("track").send().unwrap();
+        player.stop().unwrap();
+        drop(player);
+
+        match mock.next_outgoing().await {
+            Some(OutgoingEvent::Play(play)) => {
+                assert_eq!(play.guild_id, GuildId(2));
+                assert_eq!(play.track, "track");
+            }
+            other => panic!("expected play, got {:?}", other),
+        }
+        assert!(matches!(
+            mock.next_outgoing().await,
+            Some(OutgoingEvent::Stop(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_reconnects() {
+        let (node, _events, mut mock) = MockNode::connect(config(5000), PlayerManager::new());
+        let mut node_events = node.events();
+
+        mock.disconnect();
+        node.send(Stop::new(GuildId(2))).unwrap();
+
+        assert!(matches!(
+            node_events.next().await,
+            Some(NodeEvent::Disconnected { .. })
+        ));
+        assert!(matches!(
+            node_events.next().await,
+            Some(NodeEvent::Reconnected)
+        ));
+
+        // The event which failed to send is sent again after reconnecting.
+        assert!(matches!(
+            mock.next_outgoing().await,
+            Some(OutgoingEvent::Stop(stop)) if stop.guild_id == GuildId(2)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_mock_ends_connection() {
+        let (node, mut events, mock) = MockNode::connect(config(5000), PlayerManager::new());
+        drop(mock);
+
+        assert!(events.next().await.is_none());
+        assert!(node.send(Stop::new(GuildId(2))).is_err());
+    }
+}
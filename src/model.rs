@@ -20,24 +20,58 @@ pub enum Opcode {
     Update,
     /// Destroy a player.
     Destroy,
+    /// Seek a player's active track to a new position.
+    Seek,
+    /// Pause or resume a player.
+    Pause,
+    /// Set the volume of a player.
+    Volume,
+    /// Set the filters of a player.
+    Filters,
+    /// Configure how long events are buffered for after a disconnect.
+    #[serde(rename = "event-buffer")]
+    EventBuffer,
+    /// The state of a player, in reply to retrieving a player.
+    Player,
     /// An update about a player's current track.
     PlayerUpdate,
     /// Meta information about a track starting or ending.
     Event,
     /// Updated statistics about a node.
     Stats,
+    /// An opcode that is not modelled by the library.
+    ///
+    /// Unrecognised opcodes are deserialized as this, and it can't be
+    /// serialized.
+    #[serde(other, skip_serializing)]
+    Unknown,
 }
 
 pub mod outgoing {
     //! Events that clients send to Lavalink.
 
     use super::Opcode;
-    use serde::{Deserialize, Serialize};
+    use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize};
+    use serde_json::{Error as JsonError, Value};
     use serde_with::skip_serializing_none;
-    use twilight_model::{gateway::payload::VoiceServerUpdate, id::GuildId};
+    use std::{
+        convert::TryFrom,
+        error::Error,
+        fmt::{Display, Formatter, Result as FmtResult},
+    };
+    use twilight_model::{
+        gateway::payload::{VoiceServerUpdate, VoiceStateUpdate},
+        id::{GuildId, UserId},
+    };
 
     /// An outgoing event to send to Lavalink.
-    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    ///
+    /// The event is deserialized based on its `op` field, since events such as
+    /// [`Stop`] and [`Destroy`] have the same fields.
+    ///
+    /// [`Destroy`]: struct.Destroy.html
+    /// [`Stop`]: struct.Stop.html
+    #[derive(Clone, Debug, PartialEq, Serialize)]
     #[serde(untagged)]
     pub enum OutgoingEvent {
         /// A combined voice server and voice state update.
@@ -52,6 +86,16 @@ pub mod outgoing {
         Update(Update),
         /// Destroy a player for a guild.
         Destroy(Destroy),
+        /// Seek a player's active track to a new position.
+        Seek(Seek),
+        /// Pause or resume a player.
+        Pause(Pause),
+        /// Set the volume of a player.
+        SetVolume(SetVolume),
+        /// Set the filters of a player.
+        SetFilters(SetFilters),
+        /// Configure how long events are buffered for after a disconnect.
+        EventBuffer(EventBuffer),
     }
 
     impl OutgoingEvent {
@@ -64,20 +108,74 @@ pub mod outgoing {
                 OutgoingEvent::Stop(data) => data.op,
                 OutgoingEvent::Update(data) => data.op,
                 OutgoingEvent::Destroy(data) => data.op,
+                OutgoingEvent::Seek(data) => data.op,
+                OutgoingEvent::Pause(data) => data.op,
+                OutgoingEvent::SetVolume(data) => data.op,
+                OutgoingEvent::SetFilters(data) => data.op,
+                OutgoingEvent::EventBuffer(data) => data.op,
             }
         }
 
         /// Get the event guild id.
-        pub fn guild_id(&self) -> GuildId {
+        ///
+        /// Returns `None` if the event isn't for a guild, such as [`EventBuffer`].
+        ///
+        /// [`EventBuffer`]: struct.EventBuffer.html
+        pub fn guild_id(&self) -> Option<GuildId> {
             match self {
-                OutgoingEvent::VoiceUpdate(data) => data.guild_id,
-                OutgoingEvent::GetPlayer(data) => data.guild_id,
-                OutgoingEvent::Play(data) => data.guild_id,
-                OutgoingEvent::Stop(data) => data.guild_id,
-                OutgoingEvent::Update(data) => data.guild_id,
-                OutgoingEvent::Destroy(data) => data.guild_id,
+                OutgoingEvent::VoiceUpdate(data) => Some(data.guild_id),
+                OutgoingEvent::GetPlayer(data) => Some(data.guild_id),
+                OutgoingEvent::Play(data) => Some(data.guild_id),
+                OutgoingEvent::Stop(data) => Some(data.guild_id),
+                OutgoingEvent::Update(data) => Some(data.guild_id),
+                OutgoingEvent::Destroy(data) => Some(data.guild_id),
+                OutgoingEvent::Seek(data) => Some(data.guild_id),
+                OutgoingEvent::Pause(data) => Some(data.guild_id),
+                OutgoingEvent::SetVolume(data) => Some(data.guild_id),
+                OutgoingEvent::SetFilters(data) => Some(data.guild_id),
+                OutgoingEvent::EventBuffer(_) => None,
             }
         }
+
+        /// Serialize the event as the JSON sent to the node.
+        ///
+        /// This can be used to log events as they are sent over the websocket.
+        pub fn to_json(&self) -> Result<String, JsonError> {
+            serde_json::to_string(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for OutgoingEvent {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = Value::deserialize(deserializer)?;
+
+            let op = value
+                .get("op")
+                .ok_or_else(|| DeError::missing_field("op"))
+                .and_then(|op| Opcode::deserialize(op).map_err(DeError::custom))?;
+
+            let event = match op {
+                Opcode::VoiceUpdate => VoiceUpdate::deserialize(value).map(Self::VoiceUpdate),
+                Opcode::GetPlayer => GetPlayer::deserialize(value).map(Self::GetPlayer),
+                Opcode::Play => Play::deserialize(value).map(Self::Play),
+                Opcode::Stop => Stop::deserialize(value).map(Self::Stop),
+                Opcode::Update => Update::deserialize(value).map(Self::Update),
+                Opcode::Destroy => Destroy::deserialize(value).map(Self::Destroy),
+                Opcode::Seek => Seek::deserialize(value).map(Self::Seek),
+                Opcode::Pause => Pause::deserialize(value).map(Self::Pause),
+                Opcode::Volume => SetVolume::deserialize(value).map(Self::SetVolume),
+                Opcode::Filters => SetFilters::deserialize(value).map(Self::SetFilters),
+                Opcode::EventBuffer => EventBuffer::deserialize(value).map(Self::EventBuffer),
+                op => {
+                    return Err(DeError::custom(format_args!(
+                        "{:?} is not an outgoing opcode",
+                        op
+                    )))
+                }
+            };
+
+            event.map_err(DeError::custom)
+        }
     }
 
     impl From<VoiceUpdate> for OutgoingEvent {
@@ -116,6 +214,36 @@ pub mod outgoing {
         }
     }
 
+    impl From<Seek> for OutgoingEvent {
+        fn from(event: Seek) -> OutgoingEvent {
+            Self::Seek(event)
+        }
+    }
+
+    impl From<Pause> for OutgoingEvent {
+        fn from(event: Pause) -> OutgoingEvent {
+            Self::Pause(event)
+        }
+    }
+
+    impl From<SetVolume> for OutgoingEvent {
+        fn from(event: SetVolume) -> OutgoingEvent {
+            Self::SetVolume(event)
+        }
+    }
+
+    impl From<SetFilters> for OutgoingEvent {
+        fn from(event: SetFilters) -> OutgoingEvent {
+            Self::SetFilters(event)
+        }
+    }
+
+    impl From<EventBuffer> for OutgoingEvent {
+        fn from(event: EventBuffer) -> OutgoingEvent {
+            Self::EventBuffer(event)
+        }
+    }
+
     /// A combined voice server and voice state update.
     #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -144,6 +272,182 @@ pub mod outgoing {
                 event,
             }
         }
+
+        /// Create a new voice update event from the bot's voice state update
+        /// and the voice server update received from the gateway.
+        ///
+        /// The gateway sends these two events in either order when joining a
+        /// voice channel, so both need to be received before creating this.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`VoiceUpdateError::MissingEndpoint`] if the voice server
+        /// update has no endpoint. This means that the voice server is
+        /// unavailable, and another voice server update will be sent once a
+        /// new one has been allocated.
+        ///
+        /// [`VoiceUpdateError::MissingEndpoint`]: enum.VoiceUpdateError.html#variant.MissingEndpoint
+        pub fn from_gateway(
+            guild_id: GuildId,
+            voice_state_update: &VoiceStateUpdate,
+            voice_server_update: &VoiceServerUpdate,
+        ) -> Result<Self, VoiceUpdateError> {
+            if voice_server_update.endpoint.is_none() {
+                return Err(VoiceUpdateError::MissingEndpoint { guild_id });
+            }
+
+            Ok(Self::new(
+                guild_id,
+                voice_state_update.0.session_id.clone(),
+                SlimVoiceServerUpdate::from(voice_server_update.clone()),
+            ))
+        }
+    }
+
+    impl TryFrom<(&VoiceStateUpdate, &VoiceServerUpdate)> for VoiceUpdate {
+        type Error = VoiceUpdateError;
+
+        /// Create a new voice update event, using the guild ID of the voice
+        /// server update or voice state update.
+        ///
+        /// Refer to [`from_gateway`] for the errors that can occur.
+        ///
+        /// [`from_gateway`]: #method.from_gateway
+        fn try_from(
+            (voice_state_update, voice_server_update): (&VoiceStateUpdate, &VoiceServerUpdate),
+        ) -> Result<Self, Self::Error> {
+            let guild_id = voice_server_update
+                .guild_id
+                .or(voice_state_update.0.guild_id)
+                .ok_or(VoiceUpdateError::MissingGuildId)?;
+
+            Self::from_gateway(guild_id, voice_state_update, voice_server_update)
+        }
+    }
+
+    /// An error that can occur while creating a voice update from gateway
+    /// events.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub enum VoiceUpdateError {
+        /// The voice server update has no endpoint, so the voice server is
+        /// unavailable.
+        ///
+        /// Wait for the next voice server update for the guild.
+        MissingEndpoint {
+            /// The ID of the guild.
+            guild_id: GuildId,
+        },
+        /// Neither of the events have a guild ID.
+        MissingGuildId,
+    }
+
+    impl Display for VoiceUpdateError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+            match self {
+                Self::MissingEndpoint { guild_id } => write!(
+                    f,
+                    "the voice server update for guild {} has no endpoint",
+                    guild_id
+                ),
+                Self::MissingGuildId => f.write_str("the voice events have no guild id"),
+            }
+        }
+    }
+
+    impl Error for VoiceUpdateError {}
+
+    /// Combine the bot's voice state updates and voice server updates for a
+    /// guild into voice updates.
+    ///
+    /// The gateway sends these two events in either order when joining a voice
+    /// channel. Feed every voice state update and voice server update into
+    /// this, and a voice update is returned once both have been received and
+    /// the voice server is available.
+    ///
+    /// Events for other guilds and other users' voice states are ignored. A new
+    /// voice update is returned whenever either event changes, such as when
+    /// the bot is moved to another channel, and the state is reset when the bot
+    /// leaves its voice channel.
+    ///
+    /// This isn't needed when using [`Lavalink::process`], which uses an
+    /// accumulator for every guild.
+    ///
+    /// [`Lavalink::process`]: ../../client/struct.Lavalink.html#method.process
+    #[derive(Clone, Debug)]
+    pub struct VoiceUpdateAccumulator {
+        user_id: UserId,
+        guild_id: GuildId,
+        session_id: Option<String>,
+        server: Option<VoiceServerUpdate>,
+    }
+
+    impl VoiceUpdateAccumulator {
+        /// Create a new accumulator for the bot's voice updates in a guild.
+        pub fn new(user_id: UserId, guild_id: GuildId) -> Self {
+            Self {
+                user_id,
+                guild_id,
+                session_id: None,
+                server: None,
+            }
+        }
+
+        /// Feed a voice state update, returning a voice update if the voice
+        /// server update has already been received.
+        pub fn voice_state(&mut self, update: &VoiceStateUpdate) -> Option<VoiceUpdate> {
+            if update.0.user_id != self.user_id || update.0.guild_id != Some(self.guild_id) {
+                return None;
+            }
+
+            if update.0.channel_id.is_none() {
+                self.reset();
+
+                return None;
+            }
+
+            self.session_id.replace(update.0.session_id.clone());
+
+            self.voice_update()
+        }
+
+        /// Feed a voice server update, returning a voice update if the voice
+        /// state update has already been received.
+        ///
+        /// A voice server update without an endpoint is discarded, since the
+        /// voice server is unavailable.
+        pub fn voice_server(&mut self, update: &VoiceServerUpdate) -> Option<VoiceUpdate> {
+            if update.guild_id != Some(self.guild_id) {
+                return None;
+            }
+
+            if update.endpoint.is_none() {
+                self.server.take();
+
+                return None;
+            }
+
+            self.server.replace(update.clone());
+
+            self.voice_update()
+        }
+
+        /// Clear the received events, such as after the bot's shard
+        /// reconnects.
+        pub fn reset(&mut self) {
+            self.session_id.take();
+            self.server.take();
+        }
+
+        fn voice_update(&self) -> Option<VoiceUpdate> {
+            match (self.session_id.as_ref(), self.server.as_ref()) {
+                (Some(session_id), Some(server)) => Some(VoiceUpdate::new(
+                    self.guild_id,
+                    session_id.clone(),
+                    SlimVoiceServerUpdate::from(server.clone()),
+                )),
+                _ => None,
+            }
+        }
     }
 
     /// A slimmed version of a twilight voice server update.
@@ -167,6 +471,12 @@ pub mod outgoing {
     }
 
     /// Retrieve a player.
+    ///
+    /// The node replies with an [`IncomingEvent::Player`] for the same guild
+    /// ID. Replies for a guild arrive in the order that the requests were sent,
+    /// so the next reply with the guild ID is the reply to the request.
+    ///
+    /// [`IncomingEvent::Player`]: ../incoming/enum.IncomingEvent.html#variant.Player
     #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct GetPlayer {
@@ -187,6 +497,9 @@ pub mod outgoing {
     }
 
     /// Play a track, optionally specifying to not skip the current track.
+    ///
+    /// Andesite reads the start and end times as `start` and `end`, so they're
+    /// serialized under those keys.
     #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Play {
@@ -197,10 +510,18 @@ pub mod outgoing {
         /// The base64 track information.
         pub track: String,
         /// The position in milliseconds to start the track from.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(
+            alias = "startTime",
+            rename = "start",
+            skip_serializing_if = "Option::is_none"
+        )]
         pub start_time: Option<u64>,
-        /// The position in milliseconds to end the track. Does nothing.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        /// The position in milliseconds to stop playing the track at.
+        #[serde(
+            alias = "endTime",
+            rename = "end",
+            skip_serializing_if = "Option::is_none"
+        )]
         pub end_time: Option<u64>,
         /// Whether or not to replace the currently playing track with this new
         /// track.
@@ -255,7 +576,26 @@ pub mod outgoing {
         }
     }
 
-    /// Set the filters of a player
+    fn enabled_default() -> bool {
+        true
+    }
+
+    /// Whether two optional values are both missing, or both present and equal
+    /// according to `eq`.
+    fn option_eq<T>(a: &Option<T>, b: &Option<T>, eq: impl Fn(&T, &T) -> bool) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Set the filters of a player.
+    ///
+    /// Filters that are `None` are not sent and are left unchanged. Each
+    /// filter sends its `enabled` flag, which is `true` when created with its
+    /// `new` constructor, so a filter with `enabled` set to `false` can be sent
+    /// to disable a previously applied filter.
     #[skip_serializing_none]
     #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -270,19 +610,112 @@ pub mod outgoing {
         pub vibrato: Option<Vibrato>,
         /// The equalizer filter.
         pub equalizer: Option<Equalizer>,
-        /// The volume filter, always None.
-        #[serde(skip)]
-        pub volume: Option<()>,
+        /// The rotation filter.
+        pub rotation: Option<Rotation>,
+        /// The channel mix filter.
+        pub channel_mix: Option<ChannelMix>,
+        /// The volume filter, where 1.0 is the normal volume.
+        pub volume: Option<f64>,
     }
 
     impl Filters {
+        /// Create a builder to set only some of the filters.
+        pub fn builder() -> FiltersBuilder {
+            FiltersBuilder::new()
+        }
+
+        /// Whether none of the filters are enabled, such as for the
+        /// [default filters].
+        ///
+        /// A volume of 1.0 doesn't change the volume, so it counts as not
+        /// enabled.
+        ///
+        /// [default filters]: #impl-Default
+        pub fn is_default(&self) -> bool {
+            let enabled = |enabled: Option<bool>| enabled.unwrap_or(false);
+
+            !enabled(self.karaoke.as_ref().map(|filter| filter.enabled))
+                && !enabled(self.timescale.as_ref().map(|filter| filter.enabled))
+                && !enabled(self.tremolo.as_ref().map(|filter| filter.enabled))
+                && !enabled(self.vibrato.as_ref().map(|filter| filter.enabled))
+                && !enabled(self.equalizer.as_ref().map(|filter| filter.enabled))
+                && !enabled(self.rotation.as_ref().map(|filter| filter.enabled))
+                && !enabled(self.channel_mix.as_ref().map(|filter| filter.enabled))
+                && self
+                    .volume
+                    .is_none_or(|volume| (volume - 1.0).abs() < f64::EPSILON)
+        }
+
+        /// Whether the filters are equal to other filters, allowing values to
+        /// differ by up to `epsilon`.
+        ///
+        /// Values can change slightly after being sent to and received from a
+        /// node, so this can be used to check whether filters actually changed
+        /// before sending them.
+        pub fn approx_eq(&self, other: &Filters, epsilon: f64) -> bool {
+            let eq = |a: f64, b: f64| (a - b).abs() <= epsilon;
+
+            option_eq(&self.karaoke, &other.karaoke, |a, b| {
+                a.enabled == b.enabled
+                    && eq(a.level, b.level)
+                    && eq(a.mono_level, b.mono_level)
+                    && eq(a.filter_band, b.filter_band)
+                    && eq(a.filter_width, b.filter_width)
+            }) && option_eq(&self.timescale, &other.timescale, |a, b| {
+                a.enabled == b.enabled
+                    && eq(a.speed, b.speed)
+                    && eq(a.pitch, b.pitch)
+                    && eq(a.rate, b.rate)
+            }) && option_eq(&self.tremolo, &other.tremolo, |a, b| {
+                a.enabled == b.enabled && eq(a.frequency, b.frequency) && eq(a.depth, b.depth)
+            }) && option_eq(&self.vibrato, &other.vibrato, |a, b| {
+                a.enabled == b.enabled && eq(a.frequency, b.frequency) && eq(a.depth, b.depth)
+            }) && option_eq(&self.equalizer, &other.equalizer, |a, b| {
+                a.enabled == b.enabled
+                    && a.bands.len() == b.bands.len()
+                    && a.bands
+                        .iter()
+                        .zip(b.bands.iter())
+                        .all(|(a, b)| a.band == b.band && eq(a.gain, b.gain))
+            }) && option_eq(&self.rotation, &other.rotation, |a, b| {
+                a.enabled == b.enabled && eq(a.rotation_hz, b.rotation_hz)
+            }) && option_eq(&self.channel_mix, &other.channel_mix, |a, b| {
+                a.enabled == b.enabled
+                    && eq(a.left_to_left, b.left_to_left)
+                    && eq(a.left_to_right, b.left_to_right)
+                    && eq(a.right_to_left, b.right_to_left)
+                    && eq(a.right_to_right, b.right_to_right)
+            }) && option_eq(&self.volume, &other.volume, |a, b| eq(*a, *b))
+        }
+
+        /// Set the filters which are `Some` in `filters`, leaving the others
+        /// unchanged, like a node does when it receives them.
+        pub fn merge(&mut self, filters: Filters) {
+            self.karaoke = filters.karaoke.or_else(|| self.karaoke.take());
+            self.timescale = filters.timescale.or_else(|| self.timescale.take());
+            self.tremolo = filters.tremolo.or_else(|| self.tremolo.take());
+            self.vibrato = filters.vibrato.or_else(|| self.vibrato.take());
+            self.equalizer = filters.equalizer.or_else(|| self.equalizer.take());
+            self.rotation = filters.rotation.or_else(|| self.rotation.take());
+            self.channel_mix = filters.channel_mix.or_else(|| self.channel_mix.take());
+            self.volume = filters.volume.or(self.volume);
+        }
+
         /// Create new filters.
+        ///
+        /// Use [`builder`] to set only some of the filters.
+        ///
+        /// [`builder`]: #method.builder
+        #[allow(clippy::too_many_arguments)]
         pub fn new(
             karaoke: impl Into<Option<Karaoke>>,
             timescale: impl Into<Option<Timescale>>,
             tremolo: impl Into<Option<Tremolo>>,
             vibrato: impl Into<Option<Vibrato>>,
             equalizer: impl Into<Option<Equalizer>>,
+            rotation: impl Into<Option<Rotation>>,
+            channel_mix: impl Into<Option<ChannelMix>>,
+            volume: impl Into<Option<f64>>,
         ) -> Self {
             Self {
                 karaoke: karaoke.into(),
@@ -290,7 +723,9 @@ pub mod outgoing {
                 tremolo: tremolo.into(),
                 vibrato: vibrato.into(),
                 equalizer: equalizer.into(),
-                volume: None,
+                rotation: rotation.into(),
+                channel_mix: channel_mix.into(),
+                volume: volume.into(),
             }
         }
     }
@@ -325,10 +760,118 @@ pub mod outgoing {
                     bands: vec![],
                     enabled: false,
                 },
+                Rotation {
+                    rotation_hz: 0.0,
+                    enabled: false,
+                },
+                ChannelMix {
+                    left_to_left: 0.0,
+                    left_to_right: 0.0,
+                    right_to_left: 0.0,
+                    right_to_right: 0.0,
+                    enabled: false,
+                },
+                None,
             )
         }
     }
 
+    /// A builder for [`Filters`], leaving filters that are not set as `None`.
+    ///
+    /// # Examples
+    ///
+    /// Create filters with only a timescale:
+    ///
+    /// ```
+    /// use twilight_andesite::model::{FiltersBuilder, Timescale};
+    ///
+    /// let filters = FiltersBuilder::new()
+    ///     .timescale(Timescale::new(1.2, 1.2, 1.0))
+    ///     .build();
+    ///
+    /// let value = serde_json::to_value(&filters).unwrap();
+    /// assert_eq!(value.as_object().unwrap().len(), 1);
+    /// assert!(value.get("timescale").is_some());
+    /// ```
+    ///
+    /// [`Filters`]: struct.Filters.html
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct FiltersBuilder(Filters);
+
+    impl FiltersBuilder {
+        /// Create a new builder with no filters set.
+        pub fn new() -> Self {
+            Self(Filters::new(None, None, None, None, None, None, None, None))
+        }
+
+        /// Set the karaoke filter.
+        pub fn karaoke(mut self, karaoke: impl Into<Option<Karaoke>>) -> Self {
+            self.0.karaoke = karaoke.into();
+
+            self
+        }
+
+        /// Set the timescale filter.
+        pub fn timescale(mut self, timescale: impl Into<Option<Timescale>>) -> Self {
+            self.0.timescale = timescale.into();
+
+            self
+        }
+
+        /// Set the tremolo filter.
+        pub fn tremolo(mut self, tremolo: impl Into<Option<Tremolo>>) -> Self {
+            self.0.tremolo = tremolo.into();
+
+            self
+        }
+
+        /// Set the vibrato filter.
+        pub fn vibrato(mut self, vibrato: impl Into<Option<Vibrato>>) -> Self {
+            self.0.vibrato = vibrato.into();
+
+            self
+        }
+
+        /// Set the equalizer filter.
+        pub fn equalizer(mut self, equalizer: impl Into<Option<Equalizer>>) -> Self {
+            self.0.equalizer = equalizer.into();
+
+            self
+        }
+
+        /// Set the rotation filter.
+        pub fn rotation(mut self, rotation: impl Into<Option<Rotation>>) -> Self {
+            self.0.rotation = rotation.into();
+
+            self
+        }
+
+        /// Set the channel mix filter.
+        pub fn channel_mix(mut self, channel_mix: impl Into<Option<ChannelMix>>) -> Self {
+            self.0.channel_mix = channel_mix.into();
+
+            self
+        }
+
+        /// Set the volume filter.
+        pub fn volume(mut self, volume: impl Into<Option<f64>>) -> Self {
+            self.0.volume = volume.into();
+
+            self
+        }
+
+        /// Consume the builder, returning the filters.
+        pub fn build(self) -> Filters {
+            self.0
+        }
+    }
+
+    impl Default for FiltersBuilder {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     /// Karaoke filter.
     #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -341,8 +884,8 @@ pub mod outgoing {
         pub filter_band: f64,
         /// The filter width.
         pub filter_width: f64,
-        /// Whether is enabled, always false.
-        #[serde(skip)]
+        /// Whether the filter is enabled.
+        #[serde(default = "enabled_default")]
         pub enabled: bool,
     }
 
@@ -354,7 +897,7 @@ pub mod outgoing {
                 mono_level,
                 filter_band,
                 filter_width,
-                enabled: false,
+                enabled: true,
             }
         }
     }
@@ -369,8 +912,8 @@ pub mod outgoing {
         pub pitch: f64,
         /// Rate to play at.
         pub rate: f64,
-        /// Whether is enabled, always false.
-        #[serde(skip)]
+        /// Whether the filter is enabled.
+        #[serde(default = "enabled_default")]
         pub enabled: bool,
     }
 
@@ -381,9 +924,21 @@ pub mod outgoing {
                 speed,
                 pitch,
                 rate,
-                enabled: false,
+                enabled: true,
             }
         }
+
+        /// Create a timescale filter for the nightcore effect, playing faster
+        /// and at a higher pitch.
+        pub fn nightcore() -> Self {
+            Self::new(1.2, 1.2, 1.0)
+        }
+
+        /// Create a timescale filter for the vaporwave effect, playing slower
+        /// and at a lower pitch.
+        pub fn vaporwave() -> Self {
+            Self::new(0.8, 0.8, 1.0)
+        }
     }
 
     /// Tremolo filter.
@@ -394,8 +949,8 @@ pub mod outgoing {
         pub frequency: f64,
         /// The filter depth.
         pub depth: f64,
-        /// Whether is enabled, always false.
-        #[serde(skip)]
+        /// Whether the filter is enabled.
+        #[serde(default = "enabled_default")]
         pub enabled: bool,
     }
 
@@ -405,7 +960,7 @@ pub mod outgoing {
             Self {
                 frequency,
                 depth,
-                enabled: false,
+                enabled: true,
             }
         }
     }
@@ -418,8 +973,8 @@ pub mod outgoing {
         pub frequency: f64,
         /// The filter depth.
         pub depth: f64,
-        /// Whether is enabled, always false.
-        #[serde(skip)]
+        /// Whether the filter is enabled.
+        #[serde(default = "enabled_default")]
         pub enabled: bool,
     }
 
@@ -429,7 +984,7 @@ pub mod outgoing {
             Self {
                 frequency,
                 depth,
-                enabled: false,
+                enabled: true,
             }
         }
     }
@@ -440,21 +995,136 @@ pub mod outgoing {
     pub struct Equalizer {
         /// The bands to use as part of the equalizer.
         pub bands: Vec<EqualizerBand>,
-        /// Whether is enabled, always false.
-        #[serde(skip)]
+        /// Whether the filter is enabled.
+        #[serde(default = "enabled_default")]
         pub enabled: bool,
     }
 
     impl Equalizer {
+        /// The multipliers applied to the gain of each band for a bass boost.
+        const BASS_BOOST: [f64; 15] = [
+            1.0, 0.85, 0.7, 0.55, 0.4, 0.25, 0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        ];
+
+        /// The multipliers applied to the gain of each band for a treble boost.
+        const TREBLE_BOOST: [f64; 15] = [
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.1, 0.25, 0.4, 0.55, 0.7, 0.85, 1.0,
+        ];
+
         /// Create a new equalizer filter.
         pub fn new(bands: Vec<EqualizerBand>) -> Self {
             Self {
                 bands,
-                enabled: false,
+                enabled: true,
+            }
+        }
+
+        /// Create an equalizer filter with all 15 bands at a gain of 0.
+        pub fn flat() -> Self {
+            Self::scaled(0.0, &[0.0; 15])
+        }
+
+        /// Create an equalizer filter boosting the low bands by up to the
+        /// given gain.
+        pub fn bass_boost(gain: f64) -> Self {
+            Self::scaled(gain, &Self::BASS_BOOST)
+        }
+
+        /// Create an equalizer filter boosting the high bands by up to the
+        /// given gain.
+        pub fn treble_boost(gain: f64) -> Self {
+            Self::scaled(gain, &Self::TREBLE_BOOST)
+        }
+
+        /// Retrieve the gain of a band, if the band is set.
+        pub fn get_band(&self, band: i64) -> Option<f64> {
+            self.bands
+                .iter()
+                .find(|existing| existing.band == band)
+                .map(|existing| existing.gain)
+        }
+
+        /// Set the gain of a band, replacing the band if it's already set.
+        ///
+        /// Bands are kept sorted by their band index.
+        ///
+        /// # Errors
+        ///
+        /// Returns an [`EqualizerBandError`] if the band or gain are out of
+        /// range.
+        ///
+        /// [`EqualizerBandError`]: enum.EqualizerBandError.html
+        pub fn set_band(&mut self, band: i64, gain: f64) -> Result<(), EqualizerBandError> {
+            let new = EqualizerBand::try_new(band, gain)?;
+
+            let index = self
+                .bands
+                .iter()
+                .take_while(|existing| existing.band < band)
+                .count();
+
+            match self.bands.get_mut(index) {
+                Some(existing) if existing.band == band => existing.gain = new.gain,
+                _ => self.bands.insert(index, new),
             }
+
+            Ok(())
+        }
+
+        /// Remove a band, returning its gain if it was set.
+        pub fn remove_band(&mut self, band: i64) -> Option<f64> {
+            let index = self
+                .bands
+                .iter()
+                .position(|existing| existing.band == band)?;
+
+            Some(self.bands.remove(index).gain)
+        }
+
+        fn scaled(gain: f64, multipliers: &[f64; 15]) -> Self {
+            Self::new(
+                multipliers
+                    .iter()
+                    .enumerate()
+                    .map(|(band, multiplier)| EqualizerBand {
+                        band: band as i64,
+                        gain: gain * multiplier,
+                    })
+                    .collect(),
+            )
         }
     }
 
+    /// An error that can occur while creating an equalizer band.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum EqualizerBandError {
+        /// The band is not within 0 to 14.
+        BandOutOfRange {
+            /// The provided band.
+            band: i64,
+        },
+        /// The gain is not within -0.25 to 1.0.
+        GainOutOfRange {
+            /// The provided gain.
+            gain: f64,
+        },
+    }
+
+    impl Display for EqualizerBandError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+            match self {
+                Self::BandOutOfRange { band } => {
+                    write!(f, "the band {} is not within 0 to 14", band)
+                }
+                Self::GainOutOfRange { gain } => {
+                    write!(f, "the gain {} is not within -0.25 to 1.0", gain)
+                }
+            }
+        }
+    }
+
+    impl Error for EqualizerBandError {}
+
     /// A band of the equalizer.
     #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -465,6 +1135,89 @@ pub mod outgoing {
         pub gain: f64,
     }
 
+    impl EqualizerBand {
+        /// Create a new equalizer band, checking that the band and gain are
+        /// within the ranges accepted by Andesite.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`EqualizerBandError::BandOutOfRange`] if the band is not
+        /// within 0 to 14.
+        ///
+        /// Returns [`EqualizerBandError::GainOutOfRange`] if the gain is not
+        /// within -0.25 to 1.0.
+        ///
+        /// [`EqualizerBandError::BandOutOfRange`]: enum.EqualizerBandError.html#variant.BandOutOfRange
+        /// [`EqualizerBandError::GainOutOfRange`]: enum.EqualizerBandError.html#variant.GainOutOfRange
+        pub fn try_new(band: i64, gain: f64) -> Result<Self, EqualizerBandError> {
+            if !(0..=14).contains(&band) {
+                return Err(EqualizerBandError::BandOutOfRange { band });
+            }
+
+            if !(-0.25..=1.0).contains(&gain) {
+                return Err(EqualizerBandError::GainOutOfRange { gain });
+            }
+
+            Ok(Self { band, gain })
+        }
+    }
+
+    /// Rotation filter.
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Rotation {
+        /// The frequency of the rotation in hertz.
+        pub rotation_hz: f64,
+        /// Whether the filter is enabled.
+        #[serde(default = "enabled_default")]
+        pub enabled: bool,
+    }
+
+    impl Rotation {
+        /// Create a new rotation filter.
+        pub fn new(rotation_hz: f64) -> Self {
+            Self {
+                rotation_hz,
+                enabled: true,
+            }
+        }
+    }
+
+    /// Channel mix filter.
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ChannelMix {
+        /// The factor of the left channel mixed into the left channel.
+        pub left_to_left: f64,
+        /// The factor of the left channel mixed into the right channel.
+        pub left_to_right: f64,
+        /// The factor of the right channel mixed into the left channel.
+        pub right_to_left: f64,
+        /// The factor of the right channel mixed into the right channel.
+        pub right_to_right: f64,
+        /// Whether the filter is enabled.
+        #[serde(default = "enabled_default")]
+        pub enabled: bool,
+    }
+
+    impl ChannelMix {
+        /// Create a new channel mix filter.
+        pub fn new(
+            left_to_left: f64,
+            left_to_right: f64,
+            right_to_left: f64,
+            right_to_right: f64,
+        ) -> Self {
+            Self {
+                left_to_left,
+                left_to_right,
+                right_to_left,
+                right_to_right,
+                enabled: true,
+            }
+        }
+    }
+
     /// Update a player.
     #[skip_serializing_none]
     #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -502,8 +1255,57 @@ pub mod outgoing {
                 filters: filters.into(),
             }
         }
+
+        /// Create a new update event, checking that the volume is within the
+        /// range accepted by Andesite.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`UpdateError::VolumeOutOfRange`] if the volume is not
+        /// within 0 to 1000.
+        ///
+        /// [`UpdateError::VolumeOutOfRange`]: enum.UpdateError.html#variant.VolumeOutOfRange
+        pub fn try_new(
+            guild_id: GuildId,
+            pause: impl Into<Option<bool>>,
+            position: impl Into<Option<i64>>,
+            volume: impl Into<Option<i64>>,
+            filters: impl Into<Option<Filters>>,
+        ) -> Result<Self, UpdateError> {
+            let volume = volume.into();
+
+            if let Some(volume) = volume {
+                if !(0..=1000).contains(&volume) {
+                    return Err(UpdateError::VolumeOutOfRange { volume });
+                }
+            }
+
+            Ok(Self::new(guild_id, pause, position, volume, filters))
+        }
+    }
+
+    /// An error that can occur while creating an update event.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub enum UpdateError {
+        /// The volume is not within 0 to 1000.
+        VolumeOutOfRange {
+            /// The provided volume.
+            volume: i64,
+        },
     }
 
+    impl Display for UpdateError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+            match self {
+                Self::VolumeOutOfRange { volume } => {
+                    write!(f, "the volume {} is not within 0 to 1000", volume)
+                }
+            }
+        }
+    }
+
+    impl Error for UpdateError {}
+
     /// Destroy a player from a node.
     #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -523,6 +1325,140 @@ pub mod outgoing {
             }
         }
     }
+
+    /// Seek a player's active track to a new position.
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Seek {
+        /// The opcode of the event.
+        pub op: Opcode,
+        /// The guild ID of the player.
+        pub guild_id: GuildId,
+        /// The position in milliseconds to seek to.
+        pub position: i64,
+    }
+
+    impl Seek {
+        /// Create a new seek event.
+        pub fn new(guild_id: GuildId, position: i64) -> Self {
+            Self {
+                op: Opcode::Seek,
+                guild_id,
+                position,
+            }
+        }
+    }
+
+    /// Pause or resume a player.
+    ///
+    /// This is sent with the dedicated `pause` opcode rather than as an
+    /// [`Update`].
+    ///
+    /// [`Update`]: struct.Update.html
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Pause {
+        /// The opcode of the event.
+        pub op: Opcode,
+        /// The guild ID of the player.
+        pub guild_id: GuildId,
+        /// Whether to pause the player.
+        ///
+        /// Set to `true` to pause the player, or `false` to resume it.
+        pub pause: bool,
+    }
+
+    impl Pause {
+        /// Create a new pause event.
+        pub fn new(guild_id: GuildId, paused: bool) -> Self {
+            Self {
+                op: Opcode::Pause,
+                guild_id,
+                pause: paused,
+            }
+        }
+    }
+
+    /// Set the volume of a player.
+    ///
+    /// This is sent with the dedicated `volume` opcode rather than as an
+    /// [`Update`].
+    ///
+    /// [`Update`]: struct.Update.html
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetVolume {
+        /// The opcode of the event.
+        pub op: Opcode,
+        /// The guild ID of the player.
+        pub guild_id: GuildId,
+        /// The volume of the player from 0 to 1000. 100 is the default.
+        pub volume: i64,
+    }
+
+    impl SetVolume {
+        /// Create a new set volume event.
+        ///
+        /// The volume is clamped to be within 0 to 1000.
+        pub fn new(guild_id: GuildId, volume: i64) -> Self {
+            Self {
+                op: Opcode::Volume,
+                guild_id,
+                volume: volume.clamp(0, 1000),
+            }
+        }
+    }
+
+    /// Set the filters of a player.
+    ///
+    /// This is sent with the dedicated `filters` opcode rather than as an
+    /// [`Update`], so that the player's pause state, position, and volume
+    /// aren't changed.
+    ///
+    /// [`Update`]: struct.Update.html
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetFilters {
+        /// The opcode of the event.
+        pub op: Opcode,
+        /// The guild ID of the player.
+        pub guild_id: GuildId,
+        /// The filters of the player.
+        #[serde(flatten)]
+        pub filters: Filters,
+    }
+
+    impl SetFilters {
+        /// Create a new set filters event.
+        pub fn new(guild_id: GuildId, filters: Filters) -> Self {
+            Self {
+                op: Opcode::Filters,
+                guild_id,
+                filters,
+            }
+        }
+    }
+
+    /// Configure how long events are buffered for after a disconnect, so that
+    /// the session can be resumed.
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct EventBuffer {
+        /// The opcode of the event.
+        pub op: Opcode,
+        /// The number of milliseconds to buffer events for.
+        pub timeout: u64,
+    }
+
+    impl EventBuffer {
+        /// Create a new event buffer event.
+        pub fn new(timeout: u64) -> Self {
+            Self {
+                op: Opcode::EventBuffer,
+                timeout,
+            }
+        }
+    }
 }
 
 pub mod incoming {
@@ -530,14 +1466,32 @@ pub mod incoming {
 
     use super::outgoing::Filters;
     use super::Opcode;
-    use crate::http::Error;
-    use serde::{Deserialize, Serialize};
+    use crate::{
+        decode::{self, DecodeError},
+        http::{Error, ExceptionSeverity, TrackInfo},
+    };
+    use serde::{
+        de::{Deserializer, Error as DeError},
+        Deserialize, Serialize,
+    };
+    use serde_json::Value;
+    use std::collections::HashMap;
     use twilight_model::id::GuildId;
 
     /// An incoming event from a Lavalink node.
-    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    ///
+    /// The event is deserialized based on its `op` field, and its `type` field
+    /// for track events. Events with an unrecognised `op` or `type` are
+    /// deserialized as [`Unknown`].
+    ///
+    /// [`Unknown`]: #variant.Unknown
+    #[derive(Clone, Debug, PartialEq, Serialize)]
     #[serde(untagged)]
     pub enum IncomingEvent {
+        /// The state of a player, in reply to a [`GetPlayer`].
+        ///
+        /// [`GetPlayer`]: ../outgoing/struct.GetPlayer.html
+        Player(PlayerResponse),
         /// An update about the information of a player.
         PlayerUpdate(PlayerUpdate),
         /// New statistics about a node and its host.
@@ -554,12 +1508,16 @@ pub mod incoming {
         WebsocketClose(WebsocketClose),
         /// A player got destroyed.
         PlayerDestroy(PlayerDestroy),
+        /// An event that is not modelled by the library, containing the raw
+        /// JSON payload.
+        Unknown(Value),
     }
 
     impl IncomingEvent {
         /// Get the event opcode.
         pub fn op(&self) -> Opcode {
             match self {
+                IncomingEvent::Player(data) => data.op,
                 IncomingEvent::PlayerUpdate(data) => data.op,
                 IncomingEvent::Stats(data) => data.op,
                 IncomingEvent::TrackEnd(data) => data.op,
@@ -568,24 +1526,84 @@ pub mod incoming {
                 IncomingEvent::TrackStuck(data) => data.op,
                 IncomingEvent::WebsocketClose(data) => data.op,
                 IncomingEvent::PlayerDestroy(data) => data.op,
+                IncomingEvent::Unknown(data) => data
+                    .get("op")
+                    .and_then(|op| Opcode::deserialize(op).ok())
+                    .unwrap_or(Opcode::Unknown),
             }
         }
 
         /// Get the event guild id.
-        pub fn guild_id(&self) -> GuildId {
+        ///
+        /// Returns `None` if the event isn't for a guild, such as [`Stats`].
+        ///
+        /// [`Stats`]: struct.Stats.html
+        pub fn guild_id(&self) -> Option<GuildId> {
             match self {
-                IncomingEvent::PlayerUpdate(data) => data.guild_id,
-                IncomingEvent::Stats(_) => GuildId::default(),
-                IncomingEvent::TrackEnd(data) => data.guild_id,
-                IncomingEvent::TrackStart(data) => data.guild_id,
-                IncomingEvent::TrackException(data) => data.guild_id,
-                IncomingEvent::TrackStuck(data) => data.guild_id,
-                IncomingEvent::WebsocketClose(data) => data.guild_id,
-                IncomingEvent::PlayerDestroy(data) => data.guild_id,
+                IncomingEvent::Player(data) => Some(data.guild_id),
+                IncomingEvent::PlayerUpdate(data) => Some(data.guild_id),
+                IncomingEvent::Stats(_) => None,
+                IncomingEvent::TrackEnd(data) => Some(data.guild_id),
+                IncomingEvent::TrackStart(data) => Some(data.guild_id),
+                IncomingEvent::TrackException(data) => Some(data.guild_id),
+                IncomingEvent::TrackStuck(data) => Some(data.guild_id),
+                IncomingEvent::WebsocketClose(data) => Some(data.guild_id),
+                IncomingEvent::PlayerDestroy(data) => Some(data.guild_id),
+                IncomingEvent::Unknown(data) => data
+                    .get("guildId")
+                    .and_then(Value::as_str)
+                    .and_then(|id| id.parse().ok())
+                    .map(GuildId),
             }
         }
     }
 
+    impl<'de> Deserialize<'de> for IncomingEvent {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = Value::deserialize(deserializer)?;
+
+            let op = value.get("op").and_then(|op| Opcode::deserialize(op).ok());
+            let kind = value
+                .get("type")
+                .and_then(|kind| TrackEventType::deserialize(kind).ok());
+
+            let event = match (op, kind) {
+                (Some(Opcode::Player), _) => PlayerResponse::deserialize(value).map(Self::Player),
+                (Some(Opcode::PlayerUpdate), _) => {
+                    PlayerUpdate::deserialize(value).map(Self::PlayerUpdate)
+                }
+                (Some(Opcode::Stats), _) => Stats::deserialize(value).map(Self::Stats),
+                (Some(Opcode::Event), Some(TrackEventType::Start)) => {
+                    TrackStart::deserialize(value).map(Self::TrackStart)
+                }
+                (Some(Opcode::Event), Some(TrackEventType::End)) => {
+                    TrackEnd::deserialize(value).map(Self::TrackEnd)
+                }
+                (Some(Opcode::Event), Some(TrackEventType::Exception)) => {
+                    TrackException::deserialize(value).map(Self::TrackException)
+                }
+                (Some(Opcode::Event), Some(TrackEventType::Stuck)) => {
+                    TrackStuck::deserialize(value).map(Self::TrackStuck)
+                }
+                (Some(Opcode::Event), Some(TrackEventType::WebsocketClose)) => {
+                    WebsocketClose::deserialize(value).map(Self::WebsocketClose)
+                }
+                (Some(Opcode::Event), Some(TrackEventType::PlayerDestroy)) => {
+                    PlayerDestroy::deserialize(value).map(Self::PlayerDestroy)
+                }
+                _ => return Ok(Self::Unknown(value)),
+            };
+
+            event.map_err(DeError::custom)
+        }
+    }
+
+    impl From<PlayerResponse> for IncomingEvent {
+        fn from(event: PlayerResponse) -> IncomingEvent {
+            Self::Player(event)
+        }
+    }
+
     impl From<PlayerUpdate> for IncomingEvent {
         fn from(event: PlayerUpdate) -> IncomingEvent {
             Self::PlayerUpdate(event)
@@ -598,6 +1616,20 @@ pub mod incoming {
         }
     }
 
+    /// The state of a player, in reply to a [`GetPlayer`].
+    ///
+    /// [`GetPlayer`]: ../outgoing/struct.GetPlayer.html
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct PlayerResponse {
+        /// The opcode of the event.
+        pub op: Opcode,
+        /// The guild ID of the player.
+        pub guild_id: GuildId,
+        /// The state of the player, if the node has a player for the guild.
+        pub player: Option<PlayerUpdateState>,
+    }
+
     /// An update about the information of a player.
     #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -629,15 +1661,29 @@ pub mod incoming {
         pub filters: Filters,
         /// Whether the player is destroyed.
         pub destroyed: Option<bool>,
-        /// Mixer, always None.
-        #[serde(skip)]
-        pub mixer: Option<()>,
-        /// Mixer enabled, always None.
-        #[serde(skip)]
-        pub mixer_enabled: Option<()>,
-        /// Frame loss and success, always None.
-        #[serde(skip)]
-        pub frame: Option<()>,
+        /// The states of the players in the player's mixer, keyed by the
+        /// players' keys.
+        ///
+        /// The mixer allows playing multiple tracks at once in a guild.
+        #[serde(default)]
+        pub mixer: HashMap<String, PlayerUpdateState>,
+        /// Whether the mixer is enabled.
+        #[serde(default)]
+        pub mixer_enabled: bool,
+        /// Statistics about the player's audio frames, if the node sent them.
+        pub frame: Option<FrameStats>,
+    }
+
+    /// Statistics about the audio frames of a player.
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct FrameStats {
+        /// The number of frames lost in the last minute.
+        pub loss: i64,
+        /// The number of frames sent successfully in the last minute.
+        pub success: i64,
+        /// Whether there is enough data for the statistics to be meaningful.
+        pub usable: bool,
     }
 
     /// Statistics about a node and its host.
@@ -645,6 +1691,10 @@ pub mod incoming {
     #[serde(rename_all = "camelCase")]
     pub struct Stats {
         /// The opcode of the event.
+        ///
+        /// This is missing from statistics retrieved over HTTP, in which case
+        /// it defaults to the stats opcode.
+        #[serde(default = "stats_op_default")]
         pub op: Opcode,
         /// The current number of total players (active and not active) within
         /// the node.
@@ -662,6 +1712,10 @@ pub mod incoming {
         pub frames: Option<StatsFrames>,
     }
 
+    fn stats_op_default() -> Opcode {
+        Opcode::Stats
+    }
+
     /// Memory information about a node and its host.
     #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -758,7 +1812,33 @@ pub mod incoming {
         /// The base64 track that was affected.
         pub track: String,
         /// The reason that the track ended.
-        pub reason: String,
+        pub reason: TrackEndReason,
+    }
+
+    /// The reason that a track ended.
+    #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    pub enum TrackEndReason {
+        /// The track finished playing.
+        Finished,
+        /// The track failed to load.
+        LoadFailed,
+        /// The track was stopped.
+        Stopped,
+        /// The track was replaced by another track.
+        Replaced,
+        /// The player was cleaned up.
+        Cleanup,
+    }
+
+    impl TrackEndReason {
+        /// Whether the next track in a queue may be started.
+        ///
+        /// This is `true` if the track finished or failed to load, and `false`
+        /// if the track was stopped, replaced, or cleaned up.
+        pub fn may_start_next(self) -> bool {
+            matches!(self, Self::Finished | Self::LoadFailed)
+        }
     }
 
     /// A track encountered exception.
@@ -783,6 +1863,13 @@ pub mod incoming {
         pub exception: Error,
     }
 
+    impl TrackException {
+        /// Retrieve the severity of the exception, if it was reported.
+        pub fn severity(&self) -> Option<ExceptionSeverity> {
+            self.exception.severity
+        }
+    }
+
     /// A track got stuck.
     #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -803,6 +1890,29 @@ pub mod incoming {
         pub threshold_ms: i64,
     }
 
+    /// Implement `decode_info` for track events, which have the track that
+    /// was affected.
+    macro_rules! impl_decode_info {
+        ($($event:ident),+) => {
+            $(
+                impl $event {
+                    /// Decode the information of the track, such as its title,
+                    /// without a request to a node.
+                    ///
+                    /// Refer to [`decode_track_blob`] for the errors that can
+                    /// occur.
+                    ///
+                    /// [`decode_track_blob`]: ../../decode/fn.decode_track_blob.html
+                    pub fn decode_info(&self) -> Result<TrackInfo, DecodeError> {
+                        decode::decode_track_blob(&self.track)
+                    }
+                }
+            )+
+        };
+    }
+
+    impl_decode_info!(TrackStart, TrackEnd, TrackException, TrackStuck);
+
     /// A websocket got closed.
     #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -818,13 +1928,120 @@ pub mod incoming {
         #[serde(skip)]
         pub user_id: Option<()>,
         /// The reason for the close of websocket.
+        #[serde(default)]
         pub reason: Option<String>,
         /// The code for this websocket close.
-        pub code: i64,
+        ///
+        /// This defaults to an unknown code of 0 if it's missing.
+        #[serde(default = "websocket_close_code_default")]
+        pub code: VoiceCloseCode,
         /// Whether it is closed by remote.
+        ///
+        /// This defaults to `false` if it's missing.
+        #[serde(default)]
         pub by_remote: bool,
     }
 
+    fn websocket_close_code_default() -> VoiceCloseCode {
+        VoiceCloseCode::Unknown(0)
+    }
+
+    /// A close code of the Discord voice gateway.
+    ///
+    /// The raw code can be retrieved using [`code`].
+    ///
+    /// [`code`]: #method.code
+    #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+    #[serde(from = "i64", into = "i64")]
+    pub enum VoiceCloseCode {
+        /// An invalid opcode was sent.
+        UnknownOpcode,
+        /// An invalid payload was sent.
+        FailedToDecodePayload,
+        /// A payload was sent before identifying.
+        NotAuthenticated,
+        /// The token sent when identifying was incorrect.
+        AuthenticationFailed,
+        /// More than one identify payload was sent.
+        AlreadyAuthenticated,
+        /// The session is no longer valid.
+        SessionNoLongerValid,
+        /// The session timed out.
+        SessionTimeout,
+        /// The voice server could not be found.
+        ServerNotFound,
+        /// An unrecognised protocol was sent.
+        UnknownProtocol,
+        /// The client was disconnected, such as by being kicked or the channel
+        /// being deleted.
+        Disconnected,
+        /// The voice server crashed.
+        VoiceServerCrashed,
+        /// An unrecognised encryption mode was sent.
+        UnknownEncryptionMode,
+        /// A code that is not documented.
+        Unknown(i64),
+    }
+
+    impl VoiceCloseCode {
+        /// Retrieve the raw close code.
+        pub fn code(self) -> i64 {
+            match self {
+                Self::UnknownOpcode => 4001,
+                Self::FailedToDecodePayload => 4002,
+                Self::NotAuthenticated => 4003,
+                Self::AuthenticationFailed => 4004,
+                Self::AlreadyAuthenticated => 4005,
+                Self::SessionNoLongerValid => 4006,
+                Self::SessionTimeout => 4009,
+                Self::ServerNotFound => 4011,
+                Self::UnknownProtocol => 4012,
+                Self::Disconnected => 4014,
+                Self::VoiceServerCrashed => 4015,
+                Self::UnknownEncryptionMode => 4016,
+                Self::Unknown(code) => code,
+            }
+        }
+
+        /// Whether the voice connection may be re-established by sending a new
+        /// voice update.
+        ///
+        /// This is `true` if the session became invalid, timed out, or the
+        /// voice server crashed.
+        pub fn is_reconnectable(self) -> bool {
+            matches!(
+                self,
+                Self::SessionNoLongerValid | Self::SessionTimeout | Self::VoiceServerCrashed
+            )
+        }
+    }
+
+    impl From<i64> for VoiceCloseCode {
+        fn from(code: i64) -> Self {
+            match code {
+                4001 => Self::UnknownOpcode,
+                4002 => Self::FailedToDecodePayload,
+                4003 => Self::NotAuthenticated,
+                4004 => Self::AuthenticationFailed,
+                4005 => Self::AlreadyAuthenticated,
+                4006 => Self::SessionNoLongerValid,
+                4009 => Self::SessionTimeout,
+                4011 => Self::ServerNotFound,
+                4012 => Self::UnknownProtocol,
+                4014 => Self::Disconnected,
+                4015 => Self::VoiceServerCrashed,
+                4016 => Self::UnknownEncryptionMode,
+                other => Self::Unknown(other),
+            }
+        }
+    }
+
+    impl From<VoiceCloseCode> for i64 {
+        fn from(code: VoiceCloseCode) -> Self {
+            code.code()
+        }
+    }
+
     /// A player got destroyed.
     #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[serde(rename_all = "camelCase")]
@@ -846,12 +2063,724 @@ pub mod incoming {
 
 pub use self::{
     incoming::{
-        IncomingEvent, PlayerDestroy, PlayerUpdate, PlayerUpdateState, Stats, StatsCpu,
-        StatsFrames, StatsMemory, TrackEnd, TrackEventType, TrackException, TrackStart, TrackStuck,
-        WebsocketClose,
+        FrameStats, IncomingEvent, PlayerDestroy, PlayerResponse, PlayerUpdate, PlayerUpdateState,
+        Stats, StatsCpu, StatsFrames, StatsMemory, TrackEnd, TrackEndReason, TrackEventType,
+        TrackException, TrackStart, TrackStuck, VoiceCloseCode, WebsocketClose,
     },
     outgoing::{
-        Destroy, Equalizer, Filters, GetPlayer, Karaoke, OutgoingEvent, Play,
-        SlimVoiceServerUpdate, Stop, Timescale, Tremolo, Update, Vibrato, VoiceUpdate,
+        ChannelMix, Destroy, Equalizer, EqualizerBand, EqualizerBandError, EventBuffer, Filters,
+        FiltersBuilder, GetPlayer, Karaoke, OutgoingEvent, Pause, Play, Rotation, Seek, SetFilters,
+        SetVolume, SlimVoiceServerUpdate, Stop, Timescale, Tremolo, Update, UpdateError, Vibrato,
+        VoiceUpdate, VoiceUpdateAccumulator, VoiceUpdateError,
     },
 };
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Destroy, Equalizer, EqualizerBand, EqualizerBandError, EventBuffer, Filters, GetPlayer,
+        IncomingEvent, Opcode, OutgoingEvent, Pause, Play, Seek, SetFilters, SetVolume,
+        SlimVoiceServerUpdate, Stop, Timescale, TrackEndReason, TrackEventType, Update,
+        VoiceCloseCode, VoiceUpdate, VoiceUpdateAccumulator, WebsocketClose,
+    };
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::{json, Value};
+    use twilight_model::{
+        gateway::payload::{VoiceServerUpdate, VoiceStateUpdate},
+        id::{ChannelId, GuildId, UserId},
+        voice::VoiceState,
+    };
+
+    /// Assert that an outgoing payload deserializes and serializes back to the
+    /// same JSON, including when deserialized and sent as an outgoing event.
+    fn outgoing<T>(payload: Value)
+    where
+        T: DeserializeOwned + Into<OutgoingEvent> + Serialize,
+    {
+        let event = serde_json::from_value::<T>(payload.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&event).unwrap(), payload);
+
+        let event = event.into();
+        assert_eq!(
+            serde_json::from_value::<OutgoingEvent>(payload.clone()).unwrap(),
+            event
+        );
+
+        let json = event.to_json().unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), payload);
+    }
+
+    /// Assert that an incoming payload deserializes, and that serializing it
+    /// and deserializing it again results in the same event.
+    fn incoming(payload: Value) -> IncomingEvent {
+        let event = serde_json::from_value::<IncomingEvent>(payload).unwrap();
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            serde_json::from_value::<IncomingEvent>(json).unwrap(),
+            event
+        );
+
+        event
+    }
+
+    fn player_state() -> Value {
+        json!({
+            "time": 1_500_000_000_000_i64,
+            "position": 30000,
+            "paused": false,
+            "volume": 100,
+            "filters": {
+                "timescale": {
+                    "speed": 1.2,
+                    "pitch": 1.2,
+                    "rate": 1.0,
+                    "enabled": true,
+                },
+            },
+            "mixer": {},
+            "mixerEnabled": false,
+            "frame": {
+                "loss": 0,
+                "success": 3000,
+                "usable": true,
+            },
+        })
+    }
+
+    #[test]
+    fn test_outgoing_round_trip() {
+        outgoing::<VoiceUpdate>(json!({
+            "op": "voiceUpdate",
+            "sessionId": "session",
+            "guildId": "1",
+            "event": {
+                "endpoint": "eu-west1.discord.media:443",
+                "token": "token",
+            },
+        }));
+        outgoing::<GetPlayer>(json!({
+            "op": "get-player",
+            "guildId": "1",
+        }));
+        outgoing::<Play>(json!({
+            "op": "play",
+            "guildId": "1",
+            "track": "track",
+            "start": 1000,
+            "end": 2000,
+            "noReplace": true,
+        }));
+        outgoing::<Stop>(json!({
+            "op": "stop",
+            "guildId": "1",
+        }));
+        outgoing::<Update>(json!({
+            "op": "update",
+            "guildId": "1",
+            "pause": true,
+            "volume": 50,
+            "filters": {
+                "equalizer": {
+                    "bands": [{ "band": 0, "gain": 0.25 }],
+                    "enabled": true,
+                },
+            },
+        }));
+        outgoing::<Destroy>(json!({
+            "op": "destroy",
+            "guildId": "1",
+        }));
+        outgoing::<Seek>(json!({
+            "op": "seek",
+            "guildId": "1",
+            "position": 30000,
+        }));
+        outgoing::<Pause>(json!({
+            "op": "pause",
+            "guildId": "1",
+            "pause": true,
+        }));
+        outgoing::<SetVolume>(json!({
+            "op": "volume",
+            "guildId": "1",
+            "volume": 50,
+        }));
+        outgoing::<SetFilters>(json!({
+            "op": "filters",
+            "guildId": "1",
+            "karaoke": {
+                "level": 1.0,
+                "monoLevel": 1.0,
+                "filterBand": 220.0,
+                "filterWidth": 100.0,
+                "enabled": true,
+            },
+            "rotation": {
+                "rotationHz": 0.2,
+                "enabled": true,
+            },
+            "volume": 0.5,
+        }));
+        outgoing::<EventBuffer>(json!({
+            "op": "event-buffer",
+            "timeout": 60000,
+        }));
+    }
+
+    #[test]
+    fn test_outgoing_requires_outgoing_op() {
+        assert!(serde_json::from_value::<OutgoingEvent>(json!({
+            "op": "playerUpdate",
+            "guildId": "1",
+        }))
+        .is_err());
+        assert!(serde_json::from_value::<OutgoingEvent>(json!({ "guildId": "1" })).is_err());
+    }
+
+    #[test]
+    fn test_incoming_round_trip() {
+        assert!(matches!(
+            incoming(json!({
+                "op": "player",
+                "guildId": "1",
+                "player": player_state(),
+            })),
+            IncomingEvent::Player(_)
+        ));
+        assert!(matches!(
+            incoming(json!({
+                "op": "playerUpdate",
+                "guildId": "1",
+                "state": player_state(),
+            })),
+            IncomingEvent::PlayerUpdate(_)
+        ));
+        assert!(matches!(
+            incoming(json!({
+                "op": "stats",
+                "players": 2,
+                "playingPlayers": 1,
+                "uptime": 60000,
+                "memory": {
+                    "allocated": 4096,
+                    "free": 1024,
+                    "reservable": 8192,
+                    "used": 3072,
+                },
+                "cpu": {
+                    "cores": 4,
+                    "lavalinkLoad": 0.1,
+                    "systemLoad": 0.2,
+                },
+                "frameStats": {
+                    "sent": 3000,
+                    "nulled": 0,
+                    "deficit": 0,
+                },
+            })),
+            IncomingEvent::Stats(_)
+        ));
+        assert!(matches!(
+            incoming(json!({
+                "op": "event",
+                "type": "TrackStartEvent",
+                "guildId": "1",
+                "track": "track",
+            })),
+            IncomingEvent::TrackStart(_)
+        ));
+        assert!(matches!(
+            incoming(json!({
+                "op": "event",
+                "type": "TrackEndEvent",
+                "guildId": "1",
+                "track": "track",
+                "reason": "FINISHED",
+            })),
+            IncomingEvent::TrackEnd(_)
+        ));
+        assert!(matches!(
+            incoming(json!({
+                "op": "event",
+                "type": "TrackExceptionEvent",
+                "guildId": "1",
+                "track": "track",
+                "error": "This video is unavailable",
+                "exception": {
+                    "class": "com.sedmelluq.discord.lavaplayer.tools.FriendlyException",
+                    "message": "This video is unavailable",
+                    "severity": "COMMON",
+                    "cause": null,
+                },
+            })),
+            IncomingEvent::TrackException(_)
+        ));
+        assert!(matches!(
+            incoming(json!({
+                "op": "event",
+                "type": "TrackStuckEvent",
+                "guildId": "1",
+                "track": "track",
+                "thresholdMs": 10000,
+            })),
+            IncomingEvent::TrackStuck(_)
+        ));
+        assert!(matches!(
+            incoming(json!({
+                "op": "event",
+                "type": "WebSocketClosedEvent",
+                "guildId": "1",
+                "reason": "Disconnected",
+                "code": 4014,
+                "byRemote": true,
+            })),
+            IncomingEvent::WebsocketClose(_)
+        ));
+        assert!(matches!(
+            incoming(json!({
+                "op": "event",
+                "type": "PlayerDestroyedEvent",
+                "guildId": "1",
+                "cleanup": false,
+            })),
+            IncomingEvent::PlayerDestroy(_)
+        ));
+    }
+
+    #[test]
+    fn test_play_keys() {
+        let play = Play::new_complex(GuildId(1), "track", 1000, 2000, true);
+
+        assert_eq!(
+            serde_json::to_value(&play).unwrap(),
+            json!({
+                "op": "play",
+                "guildId": "1",
+                "track": "track",
+                "start": 1000,
+                "end": 2000,
+                "noReplace": true,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(Play::new(GuildId(1), "track")).unwrap(),
+            json!({
+                "op": "play",
+                "guildId": "1",
+                "track": "track",
+                "noReplace": false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_play_time_aliases() {
+        let play = serde_json::from_value::<Play>(json!({
+            "op": "play",
+            "guildId": "1",
+            "track": "track",
+            "startTime": 1000,
+            "endTime": 2000,
+            "noReplace": false,
+        }))
+        .unwrap();
+
+        assert_eq!(play.start_time, Some(1000));
+        assert_eq!(play.end_time, Some(2000));
+    }
+
+    #[test]
+    fn test_filters_builder_only_timescale() {
+        let filters = Filters::builder()
+            .timescale(Timescale::new(1.2, 1.1, 1.0))
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&filters).unwrap(),
+            json!({
+                "timescale": {
+                    "speed": 1.2,
+                    "pitch": 1.1,
+                    "rate": 1.0,
+                    "enabled": true,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_equalizer_presets_cover_every_band() {
+        let presets = [
+            Equalizer::flat(),
+            Equalizer::bass_boost(0.5),
+            Equalizer::treble_boost(0.5),
+        ];
+
+        for preset in presets.iter() {
+            let bands = preset
+                .bands
+                .iter()
+                .map(|band| band.band)
+                .collect::<Vec<_>>();
+
+            assert_eq!(bands, (0..=14).collect::<Vec<_>>());
+            assert!(preset.enabled);
+        }
+
+        assert_eq!(Equalizer::bass_boost(0.5).get_band(0), Some(0.5));
+        assert_eq!(Equalizer::bass_boost(0.5).get_band(14), Some(0.0));
+        assert_eq!(Equalizer::treble_boost(0.5).get_band(14), Some(0.5));
+    }
+
+    #[test]
+    fn test_equalizer_band_try_new() {
+        assert_eq!(
+            EqualizerBand::try_new(15, 0.5),
+            Err(EqualizerBandError::BandOutOfRange { band: 15 })
+        );
+        assert_eq!(
+            EqualizerBand::try_new(-1, 0.5),
+            Err(EqualizerBandError::BandOutOfRange { band: -1 })
+        );
+        assert_eq!(
+            EqualizerBand::try_new(0, 2.0),
+            Err(EqualizerBandError::GainOutOfRange { gain: 2.0 })
+        );
+        assert_eq!(
+            EqualizerBand::try_new(14, -0.25),
+            Ok(EqualizerBand {
+                band: 14,
+                gain: -0.25,
+            })
+        );
+    }
+
+    #[test]
+    fn test_timescale_presets_round_trip() {
+        let nightcore = json!({
+            "speed": 1.2,
+            "pitch": 1.2,
+            "rate": 1.0,
+            "enabled": true,
+        });
+        let vaporwave = json!({
+            "speed": 0.8,
+            "pitch": 0.8,
+            "rate": 1.0,
+            "enabled": true,
+        });
+
+        assert_eq!(
+            serde_json::to_value(Timescale::nightcore()).unwrap(),
+            nightcore
+        );
+        assert_eq!(
+            serde_json::from_value::<Timescale>(nightcore).unwrap(),
+            Timescale::nightcore()
+        );
+        assert_eq!(
+            serde_json::to_value(Timescale::vaporwave()).unwrap(),
+            vaporwave
+        );
+        assert_eq!(
+            serde_json::from_value::<Timescale>(vaporwave).unwrap(),
+            Timescale::vaporwave()
+        );
+    }
+
+    #[test]
+    fn test_track_end_reason() {
+        let reasons = [
+            ("FINISHED", TrackEndReason::Finished, true),
+            ("LOAD_FAILED", TrackEndReason::LoadFailed, true),
+            ("STOPPED", TrackEndReason::Stopped, false),
+            ("REPLACED", TrackEndReason::Replaced, false),
+            ("CLEANUP", TrackEndReason::Cleanup, false),
+        ];
+
+        for (raw, reason, may_start_next) in reasons.iter() {
+            assert_eq!(
+                serde_json::from_value::<TrackEndReason>(json!(raw)).unwrap(),
+                *reason
+            );
+            assert_eq!(reason.may_start_next(), *may_start_next);
+        }
+    }
+
+    #[test]
+    fn test_incoming_unknown_opcode() {
+        let payload = json!({
+            "op": "foo",
+            "guildId": "1",
+            "data": [1, 2, 3],
+        });
+        let event = serde_json::from_value::<IncomingEvent>(payload.clone()).unwrap();
+
+        assert_eq!(event, IncomingEvent::Unknown(payload.clone()));
+        assert_eq!(event.op(), Opcode::Unknown);
+        assert_eq!(event.guild_id(), Some(GuildId(1)));
+        assert_eq!(serde_json::to_value(&event).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_incoming_dispatches_on_type() {
+        // Untagged, this would be a track end since it has every field of one.
+        let event = serde_json::from_value::<IncomingEvent>(json!({
+            "op": "event",
+            "type": "TrackStartEvent",
+            "guildId": "1",
+            "track": "track",
+            "reason": "FINISHED",
+        }))
+        .unwrap();
+        assert!(matches!(
+            event,
+            IncomingEvent::TrackStart(ref start) if start.kind == TrackEventType::Start
+        ));
+
+        // Untagged, this would be a track start since it has every field of one.
+        let event = serde_json::from_value::<IncomingEvent>(json!({
+            "op": "event",
+            "type": "TrackStuckEvent",
+            "guildId": "1",
+            "track": "track",
+            "thresholdMs": 10000,
+        }))
+        .unwrap();
+        assert!(matches!(event, IncomingEvent::TrackStuck(_)));
+
+        // A known type with missing fields is an error rather than another event.
+        assert!(serde_json::from_value::<IncomingEvent>(json!({
+            "op": "event",
+            "type": "TrackEndEvent",
+            "guildId": "1",
+            "track": "track",
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_opcode_unknown() {
+        assert_eq!(
+            serde_json::from_value::<Opcode>(json!("foo")).unwrap(),
+            Opcode::Unknown
+        );
+        assert_eq!(
+            serde_json::from_value::<Opcode>(json!("event-buffer")).unwrap(),
+            Opcode::EventBuffer
+        );
+        assert!(serde_json::to_value(Opcode::Unknown).is_err());
+    }
+
+    #[test]
+    fn test_equalizer_set_band() {
+        let mut equalizer = Equalizer::new(vec![
+            EqualizerBand::try_new(1, 0.1).unwrap(),
+            EqualizerBand::try_new(5, 0.5).unwrap(),
+        ]);
+
+        // Replace an existing band.
+        equalizer.set_band(5, 0.25).unwrap();
+        assert_eq!(equalizer.bands.len(), 2);
+        assert_eq!(equalizer.get_band(5), Some(0.25));
+
+        // Insert new bands, keeping them sorted.
+        equalizer.set_band(3, 0.3).unwrap();
+        equalizer.set_band(0, 0.0).unwrap();
+        equalizer.set_band(14, 1.0).unwrap();
+        let bands = equalizer
+            .bands
+            .iter()
+            .map(|band| band.band)
+            .collect::<Vec<_>>();
+        assert_eq!(bands, [0, 1, 3, 5, 14]);
+
+        assert_eq!(
+            equalizer.set_band(15, 0.0),
+            Err(EqualizerBandError::BandOutOfRange { band: 15 })
+        );
+        assert_eq!(equalizer.remove_band(3), Some(0.3));
+        assert_eq!(equalizer.get_band(3), None);
+    }
+
+    #[test]
+    fn test_filters_is_default() {
+        assert!(Filters::default().is_default());
+        assert!(Filters::builder().build().is_default());
+        assert!(Filters::builder().volume(1.0).build().is_default());
+        assert!(!Filters::builder().volume(0.5).build().is_default());
+        assert!(!Filters::builder()
+            .timescale(Timescale::nightcore())
+            .build()
+            .is_default());
+    }
+
+    #[test]
+    fn test_filters_merge() {
+        let mut filters = Filters::builder()
+            .timescale(Timescale::nightcore())
+            .volume(0.5)
+            .build();
+        filters.merge(Filters::builder().volume(1.5).build());
+
+        assert_eq!(
+            filters,
+            Filters::builder()
+                .timescale(Timescale::nightcore())
+                .volume(1.5)
+                .build()
+        );
+    }
+
+    #[test]
+    fn test_websocket_close_minimal() {
+        let close = serde_json::from_value::<WebsocketClose>(json!({
+            "op": "event",
+            "type": "WebSocketClosedEvent",
+            "guildId": "1",
+        }))
+        .unwrap();
+        assert_eq!(close.reason, None);
+        assert_eq!(close.code, VoiceCloseCode::Unknown(0));
+        assert!(!close.by_remote);
+
+        let close = serde_json::from_value::<WebsocketClose>(json!({
+            "op": "event",
+            "type": "WebSocketClosedEvent",
+            "guildId": "1",
+            "code": 4014,
+        }))
+        .unwrap();
+        assert_eq!(close.code, VoiceCloseCode::Disconnected);
+        assert!(!close.by_remote);
+
+        assert!(matches!(
+            serde_json::from_value::<IncomingEvent>(json!({
+                "op": "event",
+                "type": "WebSocketClosedEvent",
+                "guildId": "1",
+                "reason": null,
+            }))
+            .unwrap(),
+            IncomingEvent::WebsocketClose(_)
+        ));
+    }
+
+    fn voice_state(user_id: u64, channel_id: Option<u64>, session_id: &str) -> VoiceStateUpdate {
+        VoiceStateUpdate(VoiceState {
+            channel_id: channel_id.map(ChannelId),
+            deaf: false,
+            guild_id: Some(GuildId(1)),
+            member: None,
+            mute: false,
+            self_deaf: false,
+            self_mute: false,
+            self_stream: false,
+            session_id: session_id.to_owned(),
+            suppress: false,
+            token: None,
+            user_id: UserId(user_id),
+            request_to_speak_timestamp: None,
+        })
+    }
+
+    fn voice_server(guild_id: u64, endpoint: Option<&str>) -> VoiceServerUpdate {
+        VoiceServerUpdate {
+            channel_id: None,
+            endpoint: endpoint.map(ToOwned::to_owned),
+            guild_id: Some(GuildId(guild_id)),
+            token: "token".to_owned(),
+        }
+    }
+
+    fn voice_update(session_id: &str) -> VoiceUpdate {
+        VoiceUpdate::new(
+            GuildId(1),
+            session_id,
+            SlimVoiceServerUpdate {
+                endpoint: Some("endpoint".to_owned()),
+                token: "token".to_owned(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_voice_update_accumulator_either_order() {
+        let mut accumulator = VoiceUpdateAccumulator::new(UserId(2), GuildId(1));
+        assert_eq!(accumulator.voice_state(&voice_state(2, Some(3), "a")), None);
+        assert_eq!(
+            accumulator.voice_server(&voice_server(1, Some("endpoint"))),
+            Some(voice_update("a"))
+        );
+
+        let mut accumulator = VoiceUpdateAccumulator::new(UserId(2), GuildId(1));
+        assert_eq!(
+            accumulator.voice_server(&voice_server(1, Some("endpoint"))),
+            None
+        );
+        assert_eq!(
+            accumulator.voice_state(&voice_state(2, Some(3), "a")),
+            Some(voice_update("a"))
+        );
+    }
+
+    #[test]
+    fn test_voice_update_accumulator_ignores_others() {
+        let mut accumulator = VoiceUpdateAccumulator::new(UserId(2), GuildId(1));
+        assert_eq!(accumulator.voice_state(&voice_state(4, Some(3), "a")), None);
+        assert_eq!(
+            accumulator.voice_server(&voice_server(5, Some("endpoint"))),
+            None
+        );
+        assert_eq!(
+            accumulator.voice_server(&voice_server(1, Some("endpoint"))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_voice_update_accumulator_changes() {
+        let mut accumulator = VoiceUpdateAccumulator::new(UserId(2), GuildId(1));
+        accumulator.voice_state(&voice_state(2, Some(3), "a"));
+        accumulator.voice_server(&voice_server(1, Some("endpoint")));
+
+        // Moving to another channel keeps the voice server.
+        assert_eq!(
+            accumulator.voice_state(&voice_state(2, Some(4), "b")),
+            Some(voice_update("b"))
+        );
+
+        // The voice server is unavailable until the next voice server update.
+        assert_eq!(accumulator.voice_server(&voice_server(1, None)), None);
+        assert_eq!(accumulator.voice_state(&voice_state(2, Some(4), "b")), None);
+
+        // Leaving the channel resets the state.
+        accumulator.voice_server(&voice_server(1, Some("endpoint")));
+        assert_eq!(accumulator.voice_state(&voice_state(2, None, "b")), None);
+        assert_eq!(
+            accumulator.voice_server(&voice_server(1, Some("endpoint"))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_track_event_decode_info() {
+        // A track encoded by Lavaplayer.
+        const TRACK: &str = "QAAAjQIAJVJpY2sgQXN0bGV5IC0gTmV2ZXIgR29ubmEgR2l2ZSBZb3UgVXAADlJpY2tBc3RsZXlWRVZPAAAAAAADPCAAC2RRdzR3OVdnWGNRAAEAK2h0dHBzOi8vd3d3LnlvdXR1YmUuY29tL3dhdGNoP3Y9ZFF3NHc5V2dYY1EAB3lvdXR1YmUAAAAAAAAAAA==";
+
+        let start = match incoming(json!({
+            "op": "event",
+            "type": "TrackStartEvent",
+            "guildId": "1",
+            "track": TRACK,
+        })) {
+            IncomingEvent::TrackStart(start) => start,
+            other => panic!("expected track start, got {:?}", other),
+        };
+
+        let info = start.decode_info().unwrap();
+        assert_eq!(info.title, "Rick Astley - Never Gonna Give You Up");
+        assert_eq!(info.author, "RickAstleyVEVO");
+        assert_eq!(info.length, 212_000);
+    }
+}
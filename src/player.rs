@@ -10,13 +10,18 @@
 //! [send events]: struct.Player.html#method.send
 //! [read the position]: struct.Player.html#method.position
 
-use crate::{model::*, node::Node};
+use crate::{decode, model::*, node::Node};
 use dashmap::{
     mapref::one::{Ref, RefMut},
     DashMap,
 };
-use futures_channel::mpsc::TrySendError;
-use std::{fmt::Debug, sync::Arc};
+use futures_channel::mpsc::{self, TrySendError, UnboundedReceiver, UnboundedSender};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
 use twilight_model::id::GuildId;
 
 /// Retrieve and create players for guilds.
@@ -26,6 +31,7 @@ use twilight_model::id::GuildId;
 #[derive(Clone, Debug, Default)]
 pub struct PlayerManager {
     pub(crate) players: Arc<DashMap<GuildId, Player>>,
+    voice_updates: Arc<DashMap<GuildId, VoiceUpdate>>,
 }
 
 impl PlayerManager {
@@ -40,7 +46,7 @@ impl PlayerManager {
     }
 
     /// Return a mutable reference to a player by guild ID.
-    pub(crate) fn get_mut(&self, guild_id: &GuildId) -> Option<RefMut<'_, GuildId, Player>> {
+    pub fn get_mut(&self, guild_id: &GuildId) -> Option<RefMut<'_, GuildId, Player>> {
         self.players.get_mut(guild_id)
     }
 
@@ -53,9 +59,76 @@ impl PlayerManager {
     }
 
     /// Remove a player by guild ID.
+    ///
+    /// The last voice update sent for the guild is also removed.
     pub fn remove(&self, guild_id: &GuildId) -> Option<(GuildId, Player)> {
+        self.voice_updates.remove(guild_id);
+
         self.players.remove(guild_id)
     }
+
+    /// Destroy every player, sending a [`Destroy`] event to each player's node
+    /// and removing the players.
+    ///
+    /// Players are removed even if sending the event fails. Returns the result
+    /// of sending the event for each guild.
+    ///
+    /// This can be used when shutting down, followed by [`Node::close`] for
+    /// each node.
+    ///
+    /// [`Destroy`]: ../model/outgoing/struct.Destroy.html
+    /// [`Node::close`]: ../node/struct.Node.html#method.close
+    pub fn destroy_all(&self) -> Vec<(GuildId, Result<(), TrySendError<OutgoingEvent>>)> {
+        let guild_ids = self
+            .players
+            .iter()
+            .map(|player| *player.key())
+            .collect::<Vec<_>>();
+
+        guild_ids
+            .into_iter()
+            .filter_map(|guild_id| {
+                let (_, player) = self.remove(&guild_id)?;
+
+                Some((guild_id, player.node().send(Destroy::new(guild_id))))
+            })
+            .collect()
+    }
+
+    /// Return a copy of the last voice update sent to a node for a guild.
+    ///
+    /// Voice updates are recorded when they're sent through [`Node::send`],
+    /// which includes sending them through a player.
+    ///
+    /// [`Node::send`]: ../node/struct.Node.html#method.send
+    pub fn voice_update(&self, guild_id: &GuildId) -> Option<VoiceUpdate> {
+        self.voice_updates
+            .get(guild_id)
+            .map(|voice_update| voice_update.value().clone())
+    }
+
+    pub(crate) fn set_voice_update(&self, voice_update: VoiceUpdate) {
+        self.voice_updates
+            .insert(voice_update.guild_id, voice_update);
+    }
+}
+
+/// How a player repeats tracks once they end.
+///
+/// The mode is stored on the [`Player`] for track end handlers to consult; the
+/// crate doesn't replay tracks itself.
+///
+/// [`Player`]: struct.Player.html
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RepeatMode {
+    /// Don't repeat.
+    #[default]
+    Off,
+    /// Repeat the current track.
+    Track,
+    /// Repeat the whole queue.
+    Queue,
 }
 
 /// A player for a guild connected to a node.
@@ -71,6 +144,10 @@ pub struct Player {
     paused: bool,
     volume: i64,
     filters: Filters,
+    track: Option<String>,
+    track_length: Option<i64>,
+    events: Mutex<Vec<UnboundedSender<IncomingEvent>>>,
+    repeat_mode: RepeatMode,
 }
 
 impl Player {
@@ -81,11 +158,35 @@ impl Player {
             time: 0,
             position: None,
             paused: false,
-            volume: 0,
+            volume: DEFAULT_VOLUME,
             filters: Filters::default(),
+            track: None,
+            track_length: None,
+            events: Mutex::new(Vec::new()),
+            repeat_mode: RepeatMode::default(),
         }
     }
 
+    /// Retrieve a stream of the events received from the node for the
+    /// player's guild, such as when a track starts or ends.
+    ///
+    /// Each call returns a new stream receiving every subsequent event. The
+    /// stream is unbounded, and is removed once it's dropped.
+    pub fn events(&self) -> UnboundedReceiver<IncomingEvent> {
+        let (tx, rx) = mpsc::unbounded();
+
+        self.events.lock().expect("events poisoned").push(tx);
+
+        rx
+    }
+
+    pub(crate) fn emit(&self, event: &IncomingEvent) {
+        self.events
+            .lock()
+            .expect("events poisoned")
+            .retain(|tx| tx.unbounded_send(event.clone()).is_ok());
+    }
+
     /// Send an event to the player's node.
     ///
     /// Returns a `futures_channel` `TrySendError` if the node has been removed.
@@ -119,6 +220,7 @@ impl Player {
 
     fn _send(&self, event: OutgoingEvent) -> Result<(), TrySendError<OutgoingEvent>> {
         tracing::debug!(
+            guild_id = self.guild_id.0,
             "sending event on guild player {}: {:?}",
             self.guild_id,
             event
@@ -127,16 +229,296 @@ impl Player {
         self.node.send(event)
     }
 
+    /// Send an event to the player's node, recording the track so that the
+    /// player can be moved to another node.
+    ///
+    /// When a [`Play`] event replacing the current track is sent, the player
+    /// records the new track, is unpaused, and has its position set to the
+    /// start time of the track, so that it's consistent before the node sends
+    /// a player update. A [`Play`] event which doesn't replace the current
+    /// track leaves the player unchanged.
+    ///
+    /// [`Play`]: ../model/outgoing/struct.Play.html
+    pub fn send_mut(
+        &mut self,
+        event: impl Into<OutgoingEvent>,
+    ) -> Result<(), TrySendError<OutgoingEvent>> {
+        let event = event.into();
+
+        self._send(event.clone())?;
+
+        match event {
+            OutgoingEvent::Play(play) if !play.no_replace => {
+                self.position.replace(play.start_time.unwrap_or(0) as i64);
+                self.time = now();
+                self.paused = false;
+                self.set_track(Some(play.track));
+            }
+            OutgoingEvent::Stop(_) | OutgoingEvent::Destroy(_) => {
+                self.set_track(None);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Create a builder to play a track with options, such as the position to
+    /// start from and the volume and filters to play with.
+    ///
+    /// # Examples
+    ///
+    /// Play a track from 30 seconds in at half volume:
+    ///
+    /// ```no_run
+    /// use twilight_andesite::Lavalink;
+    /// # use twilight_model::id::{GuildId, UserId};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let (guild_id, user_id) = (GuildId(1), UserId(2));
+    /// # let track = String::new();
+    ///
+    /// let lavalink = Lavalink::new(user_id);
+    ///
+    /// if let Some(mut player) = lavalink.players().get_mut(&guild_id) {
+    ///     player.play(track).start(30_000).volume(50).send()?;
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn play(&mut self, track: impl Into<String>) -> PlayBuilder<'_> {
+        PlayBuilder::new(self, track.into())
+    }
+
+    /// The events needed to recreate the player on a node which doesn't have
+    /// it, from the last voice update and track sent through the player.
+    ///
+    /// The volume and filters are set if they have been changed. The track is
+    /// started from the player's estimated position, and paused if the player
+    /// is paused.
+    pub(crate) fn restore_events(&self) -> Vec<OutgoingEvent> {
+        let mut events = Vec::new();
+
+        if let Some(voice_update) = self.voice_update() {
+            events.push(voice_update.into());
+        }
+
+        if self.volume != DEFAULT_VOLUME {
+            events.push(SetVolume::new(self.guild_id, self.volume).into());
+        }
+
+        if !self.filters.is_default() {
+            events.push(SetFilters::new(self.guild_id, self.filters.clone()).into());
+        }
+
+        if let Some(track) = self.track.clone() {
+            let start_time = self
+                .estimated_position()
+                .map(|position| position.max(0) as u64);
+
+            events.push(Play::new_complex(self.guild_id, track, start_time, None, false).into());
+
+            if self.paused {
+                events.push(Pause::new(self.guild_id, true).into());
+            }
+        }
+
+        events
+    }
+
+    /// Disable all of the player's filters, updating the player's filters.
+    ///
+    /// Use [`Filters::is_default`] on the player's [filters] to check whether
+    /// there are any filters to clear first.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    ///
+    /// [`Filters::is_default`]: ../model/outgoing/struct.Filters.html#method.is_default
+    /// [filters]: #method.filters
+    pub fn clear_filters(&mut self) -> Result<(), TrySendError<OutgoingEvent>> {
+        self._send(Update::new(self.guild_id, None, None, None, Filters::default()).into())?;
+        self.filters = Filters::default();
+
+        Ok(())
+    }
+
+    /// Send an update to the player's node with only the values that differ
+    /// from the player's state, updating the player's state.
+    ///
+    /// A position is always considered changed, since the player's position
+    /// changes while playing. Filters which are `None` are left unchanged, and
+    /// the rest are compared with [`Filters::approx_eq`]. The volume is clamped
+    /// to be within 0 to 1000.
+    ///
+    /// Returns whether an update was sent, which is `false` when nothing
+    /// changed.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    ///
+    /// [`Filters::approx_eq`]: ../model/outgoing/struct.Filters.html#method.approx_eq
+    pub fn update_if_changed(
+        &mut self,
+        mut update: Update,
+    ) -> Result<bool, TrySendError<OutgoingEvent>> {
+        update.guild_id = self.guild_id;
+
+        if update.pause == Some(self.paused) {
+            update.pause.take();
+        }
+
+        update.volume = update.volume.map(|volume| volume.clamp(0, 1000));
+
+        if update.volume == Some(self.volume) {
+            update.volume.take();
+        }
+
+        if let Some(filters) = update.filters.as_ref() {
+            let mut merged = self.filters.clone();
+            merged.merge(filters.clone());
+
+            if merged.approx_eq(&self.filters, FILTER_EPSILON) {
+                update.filters.take();
+            }
+        }
+
+        if update.pause.is_none()
+            && update.position.is_none()
+            && update.volume.is_none()
+            && update.filters.is_none()
+        {
+            return Ok(false);
+        }
+
+        self._send(update.clone().into())?;
+
+        if let Some(pause) = update.pause {
+            self.paused = pause;
+        }
+
+        if let Some(position) = update.position {
+            self.position.replace(position);
+            self.time = now();
+        }
+
+        if let Some(volume) = update.volume {
+            self.volume = volume;
+        }
+
+        if let Some(filters) = update.filters {
+            self.filters.merge(filters);
+        }
+
+        Ok(true)
+    }
+
+    /// Stop the player's active track, clearing the player's track and
+    /// position.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    pub fn stop(&mut self) -> Result<(), TrySendError<OutgoingEvent>> {
+        self.send_mut(Stop::new(self.guild_id))?;
+        self.position.take();
+
+        Ok(())
+    }
+
+    /// Seek the player's active track to a position in milliseconds, updating
+    /// the player's position.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    pub fn seek(&mut self, position: i64) -> Result<(), TrySendError<OutgoingEvent>> {
+        self._send(Seek::new(self.guild_id, position).into())?;
+        self.position.replace(position);
+        self.time = now();
+
+        Ok(())
+    }
+
+    /// Set the player's volume, updating the player's volume.
+    ///
+    /// The volume is clamped to be within 0 to 1000.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    pub fn set_volume(&mut self, volume: i64) -> Result<(), TrySendError<OutgoingEvent>> {
+        let event = SetVolume::new(self.guild_id, volume);
+        let clamped = event.volume;
+
+        if volume != clamped {
+            tracing::warn!("clamped volume {} to {}", volume, clamped);
+        }
+
+        self._send(event.into())?;
+        self.volume = clamped;
+
+        Ok(())
+    }
+
+    /// Set the player's filters, updating the player's filters.
+    ///
+    /// Filters which are `None` are left unchanged. Unlike sending the filters
+    /// in an [`Update`], this doesn't change any other state of the player.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    ///
+    /// [`Update`]: ../model/outgoing/struct.Update.html
+    pub fn set_filters(&mut self, filters: Filters) -> Result<(), TrySendError<OutgoingEvent>> {
+        self._send(SetFilters::new(self.guild_id, filters.clone()).into())?;
+        self.filters.merge(filters);
+
+        Ok(())
+    }
+
+    /// Pause the player, updating whether the player is paused.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    pub fn pause(&mut self) -> Result<(), TrySendError<OutgoingEvent>> {
+        self.set_paused(true)
+    }
+
+    /// Resume the player, updating whether the player is paused.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    pub fn resume(&mut self) -> Result<(), TrySendError<OutgoingEvent>> {
+        self.set_paused(false)
+    }
+
+    fn set_paused(&mut self, paused: bool) -> Result<(), TrySendError<OutgoingEvent>> {
+        self._send(Pause::new(self.guild_id, paused).into())?;
+        self.paused = paused;
+
+        Ok(())
+    }
+
     /// Return an immutable reference to the node linked to the player.
     pub fn node(&self) -> &Node {
         &self.node
     }
 
+    /// Return a mutable reference to the node linked to the player.
+    pub(crate) fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    /// Return a copy of the last voice update sent for the player's guild.
+    ///
+    /// Refer to [`PlayerManager::voice_update`] for when this is recorded.
+    ///
+    /// [`PlayerManager::voice_update`]: struct.PlayerManager.html#method.voice_update
+    pub fn voice_update(&self) -> Option<VoiceUpdate> {
+        self.node.players().voice_update(&self.guild_id)
+    }
+
     /// Return a copy of the player's guild ID.
     pub fn guild_id(&self) -> GuildId {
         self.guild_id
     }
 
+    /// Return the base64 track that the player is playing, if any.
+    ///
+    /// This is updated when a track is played through the player, and when
+    /// the node reports that a track started or ended.
+    pub fn current_track(&self) -> Option<&str> {
+        self.track.as_deref()
+    }
+
     /// Return a copy of the player's time.
     pub fn time(&self) -> i64 {
         self.time
@@ -152,6 +534,36 @@ impl Player {
         self.position
     }
 
+    /// Return an estimate of the player's current position.
+    ///
+    /// If the player isn't paused, the time elapsed since the position was
+    /// last updated is added to it. The estimate is limited to the length of
+    /// the track, if the player's track is known.
+    pub fn estimated_position(&self) -> Option<i64> {
+        let position = self.position?;
+
+        if self.paused {
+            return Some(position);
+        }
+
+        let estimate = position + (now() - self.time).max(0);
+
+        Some(
+            self.track_length
+                .map_or(estimate, |length| estimate.min(length)),
+        )
+    }
+
+    /// Set the player's track, decoding its length once for estimating the
+    /// player's position.
+    pub(crate) fn set_track(&mut self, track: Option<String>) {
+        self.track_length = track
+            .as_deref()
+            .and_then(|track| decode::decode_track_blob(track).ok())
+            .map(|info| info.length as i64);
+        self.track = track;
+    }
+
     /// Return a mutable reference to the player's position.
     pub(crate) fn position_mut(&mut self) -> &mut Option<i64> {
         &mut self.position
@@ -177,6 +589,16 @@ impl Player {
         &mut self.volume
     }
 
+    /// Return a copy of the player's repeat mode.
+    pub fn repeat_mode(&self) -> RepeatMode {
+        self.repeat_mode
+    }
+
+    /// Set the player's repeat mode.
+    pub fn set_repeat_mode(&mut self, repeat_mode: RepeatMode) {
+        self.repeat_mode = repeat_mode;
+    }
+
     /// Return a copy of the player's filters.
     pub fn filters(&self) -> Filters {
         self.filters.clone()
@@ -187,3 +609,287 @@ impl Player {
         &mut self.filters
     }
 }
+
+/// A builder to play a track on a player with options.
+///
+/// Created by [`Player::play`].
+///
+/// [`Player::play`]: struct.Player.html#method.play
+#[derive(Debug)]
+pub struct PlayBuilder<'a> {
+    player: &'a mut Player,
+    track: String,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
+    no_replace: bool,
+    volume: Option<i64>,
+    filters: Option<Filters>,
+}
+
+impl<'a> PlayBuilder<'a> {
+    fn new(player: &'a mut Player, track: String) -> Self {
+        Self {
+            player,
+            track,
+            start_time: None,
+            end_time: None,
+            no_replace: false,
+            volume: None,
+            filters: None,
+        }
+    }
+
+    /// Set the position in milliseconds to start the track from.
+    pub fn start(mut self, start_time: u64) -> Self {
+        self.start_time.replace(start_time);
+
+        self
+    }
+
+    /// Set the position in milliseconds to end the track at.
+    pub fn end(mut self, end_time: u64) -> Self {
+        self.end_time.replace(end_time);
+
+        self
+    }
+
+    /// Set whether to keep playing the current track instead of replacing it.
+    pub fn no_replace(mut self, no_replace: bool) -> Self {
+        self.no_replace = no_replace;
+
+        self
+    }
+
+    /// Set the volume to play the track at, from 0 to 1000.
+    pub fn volume(mut self, volume: i64) -> Self {
+        self.volume.replace(volume);
+
+        self
+    }
+
+    /// Set the filters to play the track with.
+    ///
+    /// Filters which are `None` are left unchanged.
+    pub fn filters(mut self, filters: Filters) -> Self {
+        self.filters.replace(filters);
+
+        self
+    }
+
+    /// Send the events to the player's node, updating the player's state.
+    ///
+    /// A [`Play`] event is sent first, followed by an [`Update`] event if a
+    /// volume or filters were set. The node handles events in the order they
+    /// are sent, so the volume and filters apply to the new track.
+    ///
+    /// Returns a `futures_channel` `TrySendError` if the node has been removed.
+    ///
+    /// [`Play`]: ../model/outgoing/struct.Play.html
+    /// [`Update`]: ../model/outgoing/struct.Update.html
+    pub fn send(self) -> Result<(), TrySendError<OutgoingEvent>> {
+        let guild_id = self.player.guild_id;
+
+        self.player.send_mut(Play::new_complex(
+            guild_id,
+            self.track,
+            self.start_time,
+            self.end_time,
+            self.no_replace,
+        ))?;
+
+        if self.volume.is_none() && self.filters.is_none() {
+            return Ok(());
+        }
+
+        let volume = self.volume.map(|volume| volume.clamp(0, 1000));
+
+        self.player
+            ._send(Update::new(guild_id, None, None, volume, self.filters.clone()).into())?;
+
+        if let Some(volume) = volume {
+            self.player.volume = volume;
+        }
+
+        if let Some(filters) = self.filters {
+            self.player.filters.merge(filters);
+        }
+
+        Ok(())
+    }
+}
+
+/// The volume of a player which hasn't had its volume changed.
+const DEFAULT_VOLUME: i64 = 100;
+
+/// The difference allowed between filter values for them to be considered
+/// unchanged.
+const FILTER_EPSILON: f64 = 1e-6;
+
+/// The current time in milliseconds since the Unix epoch, the same as the time
+/// of player updates.
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlayerManager;
+    use crate::{
+        mock::{config, MockNode},
+        model::{Filters, OutgoingEvent, SetFilters, SetVolume, Stop, Timescale, Update},
+        node::NodeEvent,
+    };
+    use futures_util::stream::StreamExt;
+    use twilight_model::id::GuildId;
+
+    #[tokio::test]
+    async fn test_play_no_replace_keeps_track() {
+        let players = PlayerManager::new();
+        let (node, _events, _mock) = MockNode::connect(config(5000), players.clone());
+        let mut player = players.get_or_insert(GuildId(2), node);
+
+        player.play("first").send().unwrap();
+        player.play("second").no_replace(true).send().unwrap();
+        assert_eq!(player.current_track(), Some("first"));
+
+        player.play("third").send().unwrap();
+        assert_eq!(player.current_track(), Some("third"));
+    }
+
+    #[tokio::test]
+    async fn test_estimated_position_limited_to_length() {
+        // A track encoded by Lavaplayer which is 212 seconds long.
+        const TRACK: &str = "QAAAjQIAJVJpY2sgQXN0bGV5IC0gTmV2ZXIgR29ubmEgR2l2ZSBZb3UgVXAADlJpY2tBc3RsZXlWRVZPAAAAAAADPCAAC2RRdzR3OVdnWGNRAAEAK2h0dHBzOi8vd3d3LnlvdXR1YmUuY29tL3dhdGNoP3Y9ZFF3NHc5V2dYY1EAB3lvdXR1YmUAAAAAAAAAAA==";
+
+        let players = PlayerManager::new();
+        let (node, _events, _mock) = MockNode::connect(config(5000), players.clone());
+        let mut player = players.get_or_insert(GuildId(2), node);
+
+        player.play(TRACK).start(300_000).send().unwrap();
+        assert_eq!(player.estimated_position(), Some(212_000));
+
+        // The length of an undecodable track is unknown.
+        player.play("track").start(300_000).send().unwrap();
+        assert!(player.estimated_position().unwrap() >= 300_000);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_restores_volume_and_filters() {
+        let players = PlayerManager::new();
+        let (node, _events, mut mock) = MockNode::connect(config(5000), players.clone());
+        let mut node_events = node.events();
+        let filters = Filters {
+            timescale: Some(Timescale::nightcore()),
+            ..Filters::default()
+        };
+
+        {
+            let mut player = players.get_or_insert(GuildId(2), node.clone());
+            player.set_volume(50).unwrap();
+            player.set_filters(filters.clone()).unwrap();
+            player.play("track").send().unwrap();
+        }
+
+        for _ in 0..3 {
+            mock.next_outgoing().await.unwrap();
+        }
+
+        mock.disconnect();
+        node.send(Stop::new(GuildId(3))).unwrap();
+        assert!(matches!(
+            node_events.next().await,
+            Some(NodeEvent::Disconnected { .. })
+        ));
+        assert!(matches!(
+            node_events.next().await,
+            Some(NodeEvent::Reconnected)
+        ));
+
+        assert_eq!(
+            mock.next_outgoing().await,
+            Some(SetVolume::new(GuildId(2), 50).into())
+        );
+        assert_eq!(
+            mock.next_outgoing().await,
+            Some(SetFilters::new(GuildId(2), filters).into())
+        );
+        assert!(matches!(
+            mock.next_outgoing().await,
+            Some(OutgoingEvent::Play(play)) if play.track == "track"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_update_if_changed_merges_filters() {
+        let players = PlayerManager::new();
+        let (node, _events, _mock) = MockNode::connect(config(5000), players.clone());
+        let mut player = players.get_or_insert(GuildId(2), node);
+        let timescale = Filters::builder().timescale(Timescale::nightcore()).build();
+
+        player.set_filters(timescale.clone()).unwrap();
+        assert!(!player
+            .update_if_changed(Update::new(GuildId(2), None, None, None, timescale.clone()))
+            .unwrap());
+
+        let volume = Filters::builder().volume(0.5).build();
+        assert!(player
+            .update_if_changed(Update::new(GuildId(2), None, None, None, volume))
+            .unwrap());
+        assert_eq!(
+            player.filters(),
+            Filters {
+                timescale: Some(Timescale::nightcore()),
+                volume: Some(0.5),
+                ..Filters::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_filters_merges_filters() {
+        let players = PlayerManager::new();
+        let (node, _events, _mock) = MockNode::connect(config(5000), players.clone());
+        let mut player = players.get_or_insert(GuildId(2), node);
+
+        player
+            .set_filters(Filters::builder().timescale(Timescale::nightcore()).build())
+            .unwrap();
+        player
+            .set_filters(Filters::builder().volume(0.5).build())
+            .unwrap();
+        assert_eq!(
+            player.filters(),
+            Filters {
+                timescale: Some(Timescale::nightcore()),
+                volume: Some(0.5),
+                ..Filters::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_play_merges_filters() {
+        let players = PlayerManager::new();
+        let (node, _events, _mock) = MockNode::connect(config(5000), players.clone());
+        let mut player = players.get_or_insert(GuildId(2), node);
+
+        player
+            .set_filters(Filters::builder().timescale(Timescale::nightcore()).build())
+            .unwrap();
+        player
+            .play("track")
+            .filters(Filters::builder().volume(0.5).build())
+            .send()
+            .unwrap();
+        assert_eq!(
+            player.filters(),
+            Filters {
+                timescale: Some(Timescale::nightcore()),
+                volume: Some(0.5),
+                ..Filters::default()
+            }
+        );
+    }
+}